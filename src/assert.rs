@@ -2,17 +2,55 @@
 //!
 //! [Output]: https://doc.rust-lang.org/std/process/struct.Output.html
 
+use std::cmp;
+use std::collections;
+use std::env;
+use std::error::Error;
 use std::fmt;
+use std::fs;
+use std::io;
+use std::iter;
+use std::ops;
+use std::path;
 use std::process;
 use std::str;
+use std::time;
 
+#[cfg(feature = "csv")]
+use csv;
 use predicates;
 use predicates::str::PredicateStrExt;
 use predicates_core;
 use predicates_tree::CaseTreeExt;
+#[cfg(feature = "regex")]
+use regex;
+#[cfg(feature = "json")]
+use serde_json;
+#[cfg(feature = "yaml")]
+use serde_yaml;
+#[cfg(feature = "toml")]
+use toml;
 
+use cmd::describe_signal;
+use cmd::diff_buffers;
 use cmd::dump_buffer;
+use cmd::find_bytes;
 use cmd::output_fmt;
+use cmd::write_buffer;
+#[cfg(feature = "json")]
+use json;
+#[cfg(feature = "jsonschema")]
+use predicate::json_schema;
+#[cfg(feature = "toml")]
+use predicate::toml_eq;
+#[cfg(feature = "yaml")]
+use predicate::yaml_eq;
+#[cfg(feature = "serde")]
+use report;
+use run;
+use spawn;
+use spawn::ExtraFds;
+use spawn::Timings;
 
 /// Assert the state of an [`Output`].
 ///
@@ -56,10 +94,36 @@ impl OutputAssertExt for process::Output {
     }
 }
 
+/// Runs the command, collecting its output.
+///
+/// Like [`Command::output`], stdin defaults to null rather than being inherited, so commands
+/// that read input see EOF rather than hanging, and stdout and stderr default to captured.  Unlike
+/// it, when each part of the output was produced is recorded for timing assertions like
+/// [`Assert::first_byte_within`].
+///
+/// A copy with the same program, arguments, environment changes, and current directory is run,
+/// leaving the `Command` untouched.  Other settings, like stdio, aren't copied.
+///
+/// Use [`CommandRunExt::with_default_timeout`] to fail commands that hang.
+///
+/// On Windows, exit codes are also shown in hex in failure messages, since crashes are reported
+/// as `NTSTATUS` values.
+///
+/// Set `ASSERT_CMD_SHOW_ENV=1` to include the environment variables set on the command in
+/// failure messages.
+///
+/// [`Command::output`]: https://doc.rust-lang.org/std/process/struct.Command.html#method.output
+/// [`Assert::first_byte_within`]: struct.Assert.html#method.first_byte_within
+/// [`CommandRunExt::with_default_timeout`]: ../run/trait.CommandRunExt.html#tymethod.with_default_timeout
 impl<'c> OutputAssertExt for &'c mut process::Command {
     fn assert(self) -> Assert {
-        let output = self.output().unwrap();
-        Assert::new(output).append_context("command", format!("{:?}", self))
+        let mut running = spawn::spawn(self).unwrap();
+        let killed = run::kill_after(&mut running, run::default_timeout());
+        let (output, timings) = running.wait().unwrap();
+        let assert = Assert::new(output)
+            .set_timings(timings)
+            .append_command(self);
+        run::check_timeout(assert, killed)
     }
 }
 
@@ -85,6 +149,17 @@ impl<'c> OutputAssertExt for &'c mut process::Command {
 pub struct Assert {
     output: process::Output,
     context: Vec<(&'static str, Box<fmt::Display>)>,
+    timings: Option<Timings>,
+    extra_fds: ExtraFds,
+    #[cfg(unix)]
+    max_rss: Option<u64>,
+    process_group: Option<i32>,
+    stdin_consumed: Option<bool>,
+    combined: Option<Vec<u8>>,
+    line_width: Option<usize>,
+    #[cfg(feature = "regex")]
+    snapshot_filters: Vec<(regex::Regex, String)>,
+    show_parameters: bool,
 }
 
 impl Assert {
@@ -95,7 +170,76 @@ impl Assert {
         Self {
             output,
             context: vec![],
+            timings: None,
+            extra_fds: vec![],
+            #[cfg(unix)]
+            max_rss: None,
+            process_group: None,
+            stdin_consumed: None,
+            combined: None,
+            line_width: None,
+            #[cfg(feature = "regex")]
+            snapshot_filters: vec![],
+            show_parameters: false,
+        }
+    }
+
+    pub(crate) fn append_command(mut self, cmd: &process::Command) -> Self {
+        for (name, context) in command_context(cmd) {
+            self = self.append_context(name, context);
+        }
+        self
+    }
+
+    #[cfg(unix)]
+    pub(crate) fn set_extra_fds(mut self, extra_fds: ExtraFds) -> Self {
+        self.extra_fds = extra_fds;
+        self
+    }
+
+    #[cfg(unix)]
+    pub(crate) fn set_max_rss(mut self, max_rss: u64) -> Self {
+        self.max_rss = Some(max_rss);
+        self
+    }
+
+    pub(crate) fn set_process_group(mut self, process_group: i32) -> Self {
+        self.process_group = Some(process_group);
+        self
+    }
+
+    pub(crate) fn set_stdin_consumed(mut self, stdin_consumed: bool) -> Self {
+        self.stdin_consumed = Some(stdin_consumed);
+        self
+    }
+
+    #[cfg(unix)]
+    pub(crate) fn set_combined(mut self, combined: Vec<u8>) -> Self {
+        self.combined = Some(combined);
+        self
+    }
+
+    /// Add the parameters of `pred` to `message`, if requested.
+    fn explain<P>(&self, mut message: String, pred: &P) -> String
+    where
+        P: predicates_core::reflection::PredicateReflection + ?Sized,
+    {
+        if self.show_parameters {
+            message.push_str("parameters:\n");
+            write_parameters(
+                &mut message,
+                &pred.to_string(),
+                pred.parameters(),
+                pred.children(),
+                1,
+            );
         }
+        message
+    }
+
+    pub(crate) fn set_timings(mut self, timings: Timings) -> Self {
+        self.timings = Some(timings);
+        self
     }
 
     /// Clarify failures with additional context.
@@ -121,14 +265,10 @@ impl Assert {
         self
     }
 
-    /// Access the contained [`Output`].
+    /// Shorten lines of output longer than `cols` characters in failures.
     ///
-    /// [`Output`]: https://doc.rust-lang.org/std/process/struct.Output.html
-    pub fn get_output(&self) -> &process::Output {
-        &self.output
-    }
-
-    /// Ensure the command succeeded.
+    /// This keeps failures legible when the output includes, e.g., minified or encoded data.  By
+    /// default, lines are shown in full.
     ///
     /// # Examples
     ///
@@ -139,176 +279,265 @@ impl Assert {
     ///
     /// Command::cargo_bin("bin_fixture")
     ///     .unwrap()
+    ///     .env("stdout", "aGVsbG8gd29ybGQ=")
     ///     .assert()
+    ///     .with_line_width(80)
     ///     .success();
     /// ```
-    pub fn success(self) -> Self {
-        if !self.output.status.success() {
-            let actual_code = self.output.status.code().unwrap_or_else(|| {
-                panic!(
-                    "Unexpected failure.\ncode=<interrupted>\nstderr=```{}```\n{}",
-                    dump_buffer(&self.output.stderr),
-                    self
-                )
-            });
-            panic!(
-                "Unexpected failure.\ncode-{}\nstderr=```{}```\n{}",
-                actual_code,
-                dump_buffer(&self.output.stderr),
-                self
-            );
-        }
+    pub fn with_line_width(mut self, cols: usize) -> Self {
+        self.line_width = Some(cols);
         self
     }
 
-    /// Ensure the command failed.
+    /// Include the parameters of the whole predicate, e.g. expected values, in failures.
+    ///
+    /// By default, only the parts of a predicate that failed are described.
     ///
     /// # Examples
     ///
     /// ```rust,no_run
+    /// extern crate assert_cmd;
+    /// extern crate predicates;
+    ///
     /// use assert_cmd::prelude::*;
     ///
     /// use std::process::Command;
+    /// use predicates::prelude::*;
     ///
     /// Command::cargo_bin("bin_fixture")
     ///     .unwrap()
-    ///     .env("exit", "1")
+    ///     .env("stdout", "hello")
     ///     .assert()
-    ///     .failure();
+    ///     .show_parameters()
+    ///     .stdout(predicate::str::contains("hello").and(predicate::str::similar("hello\n")));
     /// ```
-    pub fn failure(self) -> Self {
-        if self.output.status.success() {
-            panic!("Unexpected success\n{}", self);
-        }
+    pub fn show_parameters(mut self) -> Self {
+        self.show_parameters = true;
         self
     }
 
-    /// Ensure the command aborted before returning a code.
-    pub fn interrupted(self) -> Self {
-        if self.output.status.code().is_some() {
-            panic!("Unexpected completion\n{}", self);
-        }
-        self
+    /// Access the contained [`Output`].
+    ///
+    /// [`Output`]: https://doc.rust-lang.org/std/process/struct.Output.html
+    pub fn get_output(&self) -> &process::Output {
+        &self.output
     }
 
-    /// Ensure the command returned the expected code.
-    ///
-    /// This uses [`IntoCodePredicate`] to provide short-hands for common cases.
+    /// Mutably access the contained [`Output`], e.g. to normalize it after asserting.
     ///
-    /// See [`predicates`] for more predicates.
+    /// Later assertions, failure messages, and [`to_snapshot`] see the changes, so make them
+    /// once the original output has been asserted on.
     ///
     /// # Examples
     ///
-    /// Accepting a predicate:
     /// ```rust,no_run
-    /// extern crate assert_cmd;
-    /// extern crate predicates;
-    ///
     /// use assert_cmd::prelude::*;
     ///
     /// use std::process::Command;
-    /// use predicates::prelude::*;
     ///
-    /// Command::cargo_bin("bin_fixture")
+    /// let mut assert = Command::cargo_bin("bin_fixture")
     ///     .unwrap()
-    ///     .env("exit", "42")
+    ///     .env("stdout", "hello  ")
     ///     .assert()
-    ///     .code(predicate::eq(42));
+    ///     .success();
+    /// let stdout = &mut assert.get_output_mut().stdout;
+    /// while stdout.ends_with(b" ") || stdout.ends_with(b"\n") {
+    ///     stdout.pop();
+    /// }
+    /// assert.stdout("hello");
     /// ```
     ///
-    /// Accepting an exit code:
+    /// [`Output`]: https://doc.rust-lang.org/std/process/struct.Output.html
+    /// [`to_snapshot`]: #method.to_snapshot
+    pub fn get_output_mut(&mut self) -> &mut process::Output {
+        &mut self.output
+    }
+
+    /// Consume the `Assert`, returning the contained [`Output`].
+    ///
+    /// The context is discarded.
+    ///
+    /// # Examples
+    ///
     /// ```rust,no_run
     /// use assert_cmd::prelude::*;
     ///
     /// use std::process::Command;
     ///
-    /// Command::cargo_bin("bin_fixture")
+    /// let output = Command::cargo_bin("bin_fixture")
     ///     .unwrap()
-    ///     .env("exit", "42")
     ///     .assert()
-    ///     .code(42);
+    ///     .success()
+    ///     .into_output();
+    /// println!("{:?}", output);
     /// ```
     ///
-    /// Accepting multiple exit codes:
+    /// [`Output`]: https://doc.rust-lang.org/std/process/struct.Output.html
+    pub fn into_output(self) -> process::Output {
+        self.output
+    }
+
+    /// Consume the `Assert`, returning `stdout` decoded as UTF-8.
+    ///
+    /// Invalid UTF-8 is replaced with `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// # Examples
+    ///
     /// ```rust,no_run
     /// use assert_cmd::prelude::*;
     ///
     /// use std::process::Command;
     ///
-    /// Command::cargo_bin("bin_fixture")
+    /// let stdout = Command::cargo_bin("bin_fixture")
     ///     .unwrap()
-    ///     .env("exit", "42")
+    ///     .env("stdout", "42")
     ///     .assert()
-    ///     .code(&[2, 42] as &[i32]);
+    ///     .success()
+    ///     .into_stdout_string();
+    /// assert_eq!(stdout, "42\n");
     /// ```
-    ///
-    /// [`predicates`]: https://docs.rs/predicates
-    /// [`IntoCodePredicate`]: trait.IntoCodePredicate.html
-    pub fn code<I, P>(self, pred: I) -> Self
-    where
-        I: IntoCodePredicate<P>,
-        P: predicates_core::Predicate<i32>,
-    {
-        self.code_impl(&pred.into_code())
+    pub fn into_stdout_string(self) -> String {
+        String::from_utf8_lossy(&self.output.stdout).into_owned()
     }
 
-    fn code_impl(self, pred: &predicates_core::Predicate<i32>) -> Self {
-        let actual_code = self
-            .output
-            .status
-            .code()
-            .unwrap_or_else(|| panic!("Command interrupted\n{}", self));
-        if let Some(case) = pred.find_case(false, &actual_code) {
-            panic!("Unexpected return code, failed {}\n{}", case.tree(), self);
-        }
-        self
+    /// Consume the `Assert`, returning `stdout` if it is valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// let stdout = Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "42")
+    ///     .assert()
+    ///     .success()
+    ///     .try_into_stdout_string()
+    ///     .unwrap();
+    /// assert_eq!(stdout, "42\n");
+    /// ```
+    pub fn try_into_stdout_string(self) -> Result<String, str::Utf8Error> {
+        String::from_utf8(self.output.stdout).map_err(|err| err.utf8_error())
     }
 
-    /// Ensure the command wrote the expected data to `stdout`.
+    /// Consume the `Assert`, returning `stderr` decoded as UTF-8.
     ///
-    /// This uses [`IntoOutputPredicate`] to provide short-hands for common cases.
-    ///
-    /// See [`predicates`] for more predicates.
+    /// Invalid UTF-8 is replaced with `U+FFFD REPLACEMENT CHARACTER`.
     ///
     /// # Examples
     ///
-    /// Accepting a bytes predicate:
     /// ```rust,no_run
-    /// extern crate assert_cmd;
-    /// extern crate predicates;
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// let stderr = Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stderr", "42")
+    ///     .assert()
+    ///     .success()
+    ///     .into_stderr_string();
+    /// assert_eq!(stderr, "42\n");
+    /// ```
+    pub fn into_stderr_string(self) -> String {
+        String::from_utf8_lossy(&self.output.stderr).into_owned()
+    }
+
+    /// Consume the `Assert`, returning `stderr` if it is valid UTF-8.
+    ///
+    /// # Examples
     ///
+    /// ```rust,no_run
     /// use assert_cmd::prelude::*;
     ///
     /// use std::process::Command;
-    /// use predicates::prelude::*;
     ///
-    /// Command::cargo_bin("bin_fixture")
+    /// let stderr = Command::cargo_bin("bin_fixture")
     ///     .unwrap()
-    ///     .env("stdout", "hello")
-    ///     .env("stderr", "world")
+    ///     .env("stderr", "42")
     ///     .assert()
-    ///     .stdout(predicate::eq(b"hello\n" as &[u8]));
+    ///     .success()
+    ///     .try_into_stderr_string()
+    ///     .unwrap();
+    /// assert_eq!(stderr, "42\n");
     /// ```
+    pub fn try_into_stderr_string(self) -> Result<String, str::Utf8Error> {
+        String::from_utf8(self.output.stderr).map_err(|err| err.utf8_error())
+    }
+
+    /// Access what the command wrote to `fd`, as captured by [`CommandRunExt::assert_with_fds`].
+    ///
+    /// # Panics
+    ///
+    /// If `fd` wasn't captured.
+    ///
+    /// # Examples
     ///
-    /// Accepting a `str` predicate:
     /// ```rust,no_run
-    /// extern crate assert_cmd;
-    /// extern crate predicates;
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// let assert = Command::new("sh")
+    ///     .arg("-c")
+    ///     .arg("echo ready >&3")
+    ///     .assert_with_fds(&[3]);
+    /// assert_eq!(assert.get_extra_fd(3), b"ready\n");
+    /// ```
+    ///
+    /// [`CommandRunExt::assert_with_fds`]: ../run/trait.CommandRunExt.html#tymethod.assert_with_fds
+    #[cfg(unix)]
+    pub fn get_extra_fd(&self, fd: i32) -> &[u8] {
+        self.extra_fds
+            .iter()
+            .find(|&&(captured, _)| captured == fd)
+            .map(|(_, buffer)| buffer.as_slice())
+            .unwrap_or_else(|| panic!("fd {} was not captured", fd))
+    }
+
+    /// The command's peak memory use in bytes, as measured by
+    /// [`CommandRunExt::assert_with_max_rss`].
+    ///
+    /// On Linux, this includes the memory of the test process the command was started from.
+    ///
+    /// # Panics
+    ///
+    /// If the memory use wasn't measured.
     ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
     /// use assert_cmd::prelude::*;
     ///
     /// use std::process::Command;
-    /// use predicates::prelude::*;
     ///
-    /// Command::cargo_bin("bin_fixture")
+    /// let assert = Command::cargo_bin("bin_fixture")
     ///     .unwrap()
-    ///     .env("stdout", "hello")
-    ///     .env("stderr", "world")
-    ///     .assert()
-    ///     .stdout(predicate::str::similar("hello\n"));
+    ///     .assert_with_max_rss();
+    /// println!("{} bytes", assert.max_rss());
     /// ```
     ///
-    /// Accepting bytes:
+    /// [`CommandRunExt::assert_with_max_rss`]: ../run/trait.CommandRunExt.html#tymethod.assert_with_max_rss
+    #[cfg(unix)]
+    pub fn max_rss(&self) -> u64 {
+        self.max_rss
+            .expect("Memory use not measured, see `CommandRunExt::assert_with_max_rss`")
+    }
+
+    /// Ensure the command's peak memory use, as measured by
+    /// [`CommandRunExt::assert_with_max_rss`], was below `bytes`.
+    ///
+    /// On Linux, the peak includes the memory of the test process the command was started from,
+    /// so leave room for it in `bytes`.
+    ///
+    /// # Panics
+    ///
+    /// If the memory use wasn't measured.
+    ///
+    /// # Examples
+    ///
     /// ```rust,no_run
     /// use assert_cmd::prelude::*;
     ///
@@ -316,153 +545,4002 @@ impl Assert {
     ///
     /// Command::cargo_bin("bin_fixture")
     ///     .unwrap()
-    ///     .env("stdout", "hello")
-    ///     .env("stderr", "world")
-    ///     .assert()
-    ///     .stdout(b"hello\n" as &[u8]);
+    ///     .assert_with_max_rss()
+    ///     .success()
+    ///     .max_rss_below(512 * 1024 * 1024);
     /// ```
     ///
-    /// Accepting a `str`:
+    /// [`CommandRunExt::assert_with_max_rss`]: ../run/trait.CommandRunExt.html#tymethod.assert_with_max_rss
+    #[cfg(unix)]
+    pub fn max_rss_below(self, bytes: u64) -> Self {
+        let max_rss = self.max_rss();
+        if bytes <= max_rss {
+            panic!(
+                "Unexpected memory use, peak of {} bytes is not below {} bytes\n{}",
+                max_rss, bytes, self
+            );
+        }
+        self
+    }
+
+    /// The command's `stdout` and `stderr` interleaved, as captured by
+    /// [`CommandRunExt::assert_combined`].
+    ///
+    /// The interleaving is in the order the command flushed its output, see
+    /// [`CommandRunExt::assert_combined`].
+    ///
+    /// # Panics
+    ///
+    /// If the output wasn't captured combined.
+    ///
+    /// # Examples
+    ///
     /// ```rust,no_run
     /// use assert_cmd::prelude::*;
     ///
     /// use std::process::Command;
     ///
-    /// Command::cargo_bin("bin_fixture")
+    /// let assert = Command::cargo_bin("bin_fixture")
     ///     .unwrap()
     ///     .env("stdout", "hello")
     ///     .env("stderr", "world")
-    ///     .assert()
-    ///     .stdout("hello\n");
+    ///     .assert_combined();
+    /// println!("{}", String::from_utf8_lossy(assert.get_combined()));
     /// ```
     ///
-    /// [`predicates`]: https://docs.rs/predicates
-    /// [`IntoOutputPredicate`]: trait.IntoOutputPredicate.html
-    pub fn stdout<I, P>(self, pred: I) -> Self
-    where
-        I: IntoOutputPredicate<P>,
-        P: predicates_core::Predicate<[u8]>,
-    {
-        self.stdout_impl(&pred.into_output())
+    /// [`CommandRunExt::assert_combined`]: ../run/trait.CommandRunExt.html#tymethod.assert_combined
+    #[cfg(unix)]
+    pub fn get_combined(&self) -> &[u8] {
+        self.combined
+            .as_ref()
+            .expect("Output not captured combined, see `CommandRunExt::assert_combined`")
     }
 
-    fn stdout_impl(self, pred: &predicates_core::Predicate<[u8]>) -> Self {
-        {
-            let actual = &self.output.stdout;
-            if let Some(case) = pred.find_case(false, &actual) {
-                panic!("Unexpected stdout, failed {}\n{}", case.tree(), self);
-            }
+    /// Ensure each of `markers` appears in the combined output, as captured by
+    /// [`CommandRunExt::assert_combined`], in order.
+    ///
+    /// The first occurrence of each marker is used.  Failures show where each was found.
+    ///
+    /// # Panics
+    ///
+    /// If the output wasn't captured combined.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("sh")
+    ///     .arg("-c")
+    ///     .arg("echo starting >&2; echo result; echo done >&2")
+    ///     .assert_combined()
+    ///     .combined_order(&["starting", "result", "done"]);
+    /// ```
+    ///
+    /// [`CommandRunExt::assert_combined`]: ../run/trait.CommandRunExt.html#tymethod.assert_combined
+    #[cfg(unix)]
+    pub fn combined_order(self, markers: &[&str]) -> Self {
+        let positions: Vec<_> = markers
+            .iter()
+            .map(|marker| find_bytes(self.get_combined(), marker.as_bytes()))
+            .collect();
+        let ordered = positions.iter().all(Option::is_some)
+            && positions.windows(2).all(|pair| pair[0] < pair[1]);
+        if !ordered {
+            let found: Vec<_> = markers
+                .iter()
+                .zip(&positions)
+                .map(|(marker, position)| match *position {
+                    Some(position) => format!("`{}` at {}", marker, position),
+                    None => format!("`{}` missing", marker),
+                })
+                .collect();
+            panic!(
+                "Unexpected order of combined output, found {}\n{}",
+                found.join(", "),
+                self
+            );
         }
         self
     }
 
-    /// Ensure the command wrote the expected data to `stderr`.
+    /// Whether all of the stdin given by [`CommandStdInExt::with_stdin`] was written to the
+    /// command, rather than it closing stdin early.
     ///
-    /// This uses [`IntoOutputPredicate`] to provide short-hands for common cases.
+    /// Input that fits in the pipe's buffer, often 64KiB, is written even if the command never
+    /// reads it, so this catches a command that stops reading only for larger input.
     ///
-    /// See [`predicates`] for more predicates.
+    /// # Panics
+    ///
+    /// If the command wasn't given stdin.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// let assert = Command::new("cat")
+    ///     .with_stdin()
+    ///     .buffer("42")
+    ///     .assert();
+    /// assert!(assert.consumed_all_stdin());
+    /// ```
+    ///
+    /// [`CommandStdInExt::with_stdin`]: ../stdin/trait.CommandStdInExt.html#tymethod.with_stdin
+    pub fn consumed_all_stdin(&self) -> bool {
+        self.stdin_consumed
+            .expect("Stdin consumption not tracked, see `CommandStdInExt::with_stdin`")
+    }
+
+    /// Ensure all of the stdin given by [`CommandStdInExt::with_stdin`] was written to the
+    /// command.
+    ///
+    /// See [`consumed_all_stdin`] for the limitations.
+    ///
+    /// # Panics
+    ///
+    /// If the command wasn't given stdin.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("cat")
+    ///     .with_stdin()
+    ///     .buffer("42")
+    ///     .assert()
+    ///     .stdin_fully_consumed();
+    /// ```
+    ///
+    /// [`CommandStdInExt::with_stdin`]: ../stdin/trait.CommandStdInExt.html#tymethod.with_stdin
+    /// [`consumed_all_stdin`]: #method.consumed_all_stdin
+    pub fn stdin_fully_consumed(self) -> Self {
+        if !self.consumed_all_stdin() {
+            panic!(
+                "Unexpected stdin handling, closed before all of stdin was written\n{}",
+                self
+            );
+        }
+        self
+    }
+
+    /// Summarize the run as a serializable report, e.g. for collecting results in CI.
+    ///
+    /// See [`AssertReport`] for what is included.  Requires the `serde` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// let report = Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .assert()
+    ///     .to_report();
+    /// assert_eq!(report.code, Some(0));
+    /// ```
+    ///
+    /// [`AssertReport`]: ../report/struct.AssertReport.html
+    #[cfg(feature = "serde")]
+    pub fn to_report(&self) -> report::AssertReport {
+        let context = self
+            .context
+            .iter()
+            .map(|&(name, ref value)| report::ReportContext {
+                name: name.to_owned(),
+                value: value.to_string(),
+            })
+            .collect();
+        report::AssertReport::new(context, &self.output)
+    }
+
+    /// Replace matches of the regex `pattern` with `replacement` in [`to_snapshot`].
+    ///
+    /// This keeps snapshots stable when the output includes timestamps, paths, or other
+    /// nondeterministic values.  `replacement` may refer to capture groups, like `$1`.
+    ///
+    /// Requires the `regex` feature, which the `insta` feature enables.
+    ///
+    /// # Panics
+    ///
+    /// If `pattern` is not a valid regex.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// let snapshot = Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "took 42ms")
+    ///     .assert()
+    ///     .snapshot_filter(r"\d+ms", "[DURATION]")
+    ///     .to_snapshot();
+    /// assert_eq!(snapshot, "code: 0\nstdout:\ntook [DURATION]\nstderr:\n");
+    /// ```
+    ///
+    /// [`to_snapshot`]: #method.to_snapshot
+    #[cfg(feature = "regex")]
+    pub fn snapshot_filter(mut self, pattern: &str, replacement: &str) -> Self {
+        let regex = regex::Regex::new(pattern)
+            .unwrap_or_else(|err| panic!("Invalid snapshot filter `{}`: {}", pattern, err));
+        self.snapshot_filters.push((regex, replacement.to_owned()));
+        self
+    }
+
+    /// Render the code, `stdout`, and `stderr` for snapshot testing.
+    ///
+    /// The rendering is stable across runs and platforms: output is decoded lossily, line
+    /// endings are normalized to `\n`, and each [`snapshot_filter`] is applied, in order, to
+    /// `stdout` and `stderr`.  The context is not included.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// let snapshot = Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .assert()
+    ///     .to_snapshot();
+    /// assert_eq!(snapshot, "code: 0\nstdout:\nhello\nstderr:\n");
+    /// ```
+    ///
+    /// [`snapshot_filter`]: #method.snapshot_filter
+    pub fn to_snapshot(&self) -> String {
+        let mut snapshot = match self.output.status.code() {
+            Some(code) => format!("code: {}\n", code),
+            None => "code: <interrupted>\n".to_owned(),
+        };
+        for &(name, buffer) in &[
+            ("stdout", &self.output.stdout),
+            ("stderr", &self.output.stderr),
+        ] {
+            let text = String::from_utf8_lossy(buffer).replace("\r\n", "\n");
+            #[cfg(feature = "regex")]
+            let text = self
+                .snapshot_filters
+                .iter()
+                .fold(text, |text, (regex, replacement)| {
+                    regex.replace_all(&text, replacement.as_str()).into_owned()
+                });
+            snapshot.push_str(name);
+            snapshot.push_str(":\n");
+            snapshot.push_str(&text);
+            if !text.is_empty() && !text.ends_with('\n') {
+                snapshot.push('\n');
+            }
+        }
+        snapshot
+    }
+
+    /// Collect failures rather than panicking on the first one.
+    ///
+    /// Call [`SoftAssert::report`] to panic with all failures.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .env("stderr", "world")
+    ///     .assert()
+    ///     .soft()
+    ///     .code(0)
+    ///     .stdout("hello\n")
+    ///     .stderr("world\n")
+    ///     .report();
+    /// ```
+    ///
+    /// [`SoftAssert::report`]: struct.SoftAssert.html#method.report
+    pub fn soft(self) -> SoftAssert {
+        SoftAssert {
+            assert: self,
+            failures: vec![],
+        }
+    }
+
+    /// Ensure the command succeeded.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .assert()
+    ///     .success();
+    /// ```
+    pub fn success(self) -> Self {
+        if let Err(err) = self.check_success() {
+            panic!("{}\n{}", err, self);
+        }
+        self
+    }
+
+    fn check_success(&self) -> Result<(), String> {
+        if !self.output.status.success() {
+            // The streams are shown by `self`, which the caller prints after this message.
+            let actual_code = self.output.status.code().ok_or_else(|| {
+                format!(
+                    "Unexpected failure.\ncode=<interrupted>\n{}",
+                    describe_signal(&self.output.status)
+                )
+                .trim_end()
+                .to_owned()
+            })?;
+            return Err(format!(
+                "Unexpected failure.\ncode-{}\nstderr=```{}```",
+                actual_code,
+                dump_buffer(&self.output.stderr)
+            ));
+        }
+        Ok(())
+    }
+
+    /// Ensure the command failed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("exit", "1")
+    ///     .assert()
+    ///     .failure();
+    /// ```
+    pub fn failure(self) -> Self {
+        if let Err(err) = self.check_failure() {
+            panic!("{}\n{}", err, self);
+        }
+        self
+    }
+
+    fn check_failure(&self) -> Result<(), String> {
+        if self.output.status.success() {
+            return Err("Unexpected success".to_owned());
+        }
+        Ok(())
+    }
+
+    /// Ensure the command failed and explained why on `stderr`.
+    ///
+    /// This catches silent failures, which leave users guessing.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("exit", "1")
+    ///     .env("stderr", "error: invalid input")
+    ///     .assert()
+    ///     .failure_with_stderr();
+    /// ```
+    pub fn failure_with_stderr(self) -> Self {
+        if let Err(err) = self.check_failure_with_stderr() {
+            panic!("{}\n{}", err, self);
+        }
+        self
+    }
+
+    fn check_failure_with_stderr(&self) -> Result<(), String> {
+        self.check_failure()?;
+        if self.output.stderr.is_empty() {
+            return Err("Unexplained failure, nothing written to stderr".to_owned());
+        }
+        Ok(())
+    }
+
+    /// Ensure the command returned a nonzero code.
+    ///
+    /// Unlike [`failure`], a command interrupted before returning a code, like by a signal, does
+    /// not pass.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("exit", "2")
+    ///     .assert()
+    ///     .nonzero_code();
+    /// ```
+    ///
+    /// [`failure`]: #method.failure
+    pub fn nonzero_code(self) -> Self {
+        if let Err(err) = self.check_nonzero_code() {
+            panic!("{}\n{}", err, self);
+        }
+        self
+    }
+
+    fn check_nonzero_code(&self) -> Result<(), String> {
+        match self.output.status.code() {
+            Some(0) => Err("Unexpected success, returned code 0".to_owned()),
+            Some(_) => Ok(()),
+            None => Err("Unexpected interruption, no code returned".to_owned()),
+        }
+    }
+
+    /// Ensure the command aborted before returning a code.
+    pub fn interrupted(self) -> Self {
+        if let Err(err) = self.check_interrupted() {
+            panic!("{}\n{}", err, self);
+        }
+        self
+    }
+
+    fn check_interrupted(&self) -> Result<(), String> {
+        if self.output.status.code().is_some() {
+            return Err("Unexpected completion".to_owned());
+        }
+        Ok(())
+    }
+
+    /// Ensure the command exited normally, returning a code, rather than being killed by a
+    /// signal.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("exit", "1")
+    ///     .assert()
+    ///     .exited_normally();
+    /// ```
+    #[cfg(unix)]
+    pub fn exited_normally(self) -> Self {
+        if let Err(err) = self.check_exited_normally() {
+            panic!("{}\n{}", err, self);
+        }
+        self
+    }
+
+    #[cfg(unix)]
+    fn check_exited_normally(&self) -> Result<(), String> {
+        use std::os::unix::process::ExitStatusExt;
+        match self.output.status.signal() {
+            Some(signal) => Err(format!(
+                "Unexpected termination, killed by signal {}",
+                signal
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Ensure the command was killed by a signal, `signal` if given.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// extern crate assert_cmd;
+    /// extern crate libc;
+    ///
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("sh")
+    ///     .arg("-c")
+    ///     .arg("kill -TERM $$")
+    ///     .assert()
+    ///     .killed_by_signal(Some(libc::SIGTERM));
+    /// ```
+    #[cfg(unix)]
+    pub fn killed_by_signal(self, signal: Option<i32>) -> Self {
+        if let Err(err) = self.check_killed_by_signal(signal) {
+            panic!("{}\n{}", err, self);
+        }
+        self
+    }
+
+    #[cfg(unix)]
+    fn check_killed_by_signal(&self, expected: Option<i32>) -> Result<(), String> {
+        use std::os::unix::process::ExitStatusExt;
+        match (self.output.status.signal(), expected) {
+            (None, _) => Err(format!(
+                "Unexpected termination, exited normally with code {}",
+                self.output.status.code().unwrap_or_default()
+            )),
+            (Some(actual), Some(expected)) if actual != expected => Err(format!(
+                "Unexpected termination, killed by signal {}, expected signal {}",
+                actual, expected
+            )),
+            (Some(_), _) => Ok(()),
+        }
+    }
+
+    /// Ensure the command returned the expected code.
+    ///
+    /// This uses [`IntoCodePredicate`] to provide short-hands for common cases.
+    ///
+    /// See [`predicates`] for more predicates.
+    ///
+    /// # Examples
+    ///
+    /// Accepting a predicate:
+    /// ```rust,no_run
+    /// extern crate assert_cmd;
+    /// extern crate predicates;
+    ///
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    /// use predicates::prelude::*;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("exit", "42")
+    ///     .assert()
+    ///     .code(predicate::eq(42));
+    /// ```
+    ///
+    /// Accepting an exit code:
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("exit", "42")
+    ///     .assert()
+    ///     .code(42);
+    /// ```
+    ///
+    /// Accepting multiple exit codes:
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("exit", "42")
+    ///     .assert()
+    ///     .code(&[2, 42] as &[i32]);
+    /// ```
+    ///
+    /// [`predicates`]: https://docs.rs/predicates
+    /// [`IntoCodePredicate`]: trait.IntoCodePredicate.html
+    pub fn code<I, P>(self, pred: I) -> Self
+    where
+        I: IntoCodePredicate<P>,
+        P: predicates_core::Predicate<i32>,
+    {
+        self.code_impl(&pred.into_code())
+    }
+
+    /// Ensure the command returned the code computed by `f`.
+    ///
+    /// `f` is only called when asserting, for expectations derived at runtime.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::env;
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("exit", "42")
+    ///     .assert()
+    ///     .code_with(|| {
+    ///         env::var("EXPECTED_CODE")
+    ///             .ok()
+    ///             .and_then(|code| code.parse().ok())
+    ///             .unwrap_or(42)
+    ///     });
+    /// ```
+    pub fn code_with<F>(self, f: F) -> Self
+    where
+        F: FnOnce() -> i32,
+    {
+        self.code_impl(&predicates::ord::eq(f()))
+    }
+
+    fn code_impl(self, pred: &dyn predicates_core::Predicate<i32>) -> Self {
+        if let Err(err) = self.check_code(pred) {
+            panic!("{}\n{}", err, self);
+        }
+        self
+    }
+
+    fn check_code(&self, pred: &dyn predicates_core::Predicate<i32>) -> Result<(), String> {
+        let actual_code = self
+            .output
+            .status
+            .code()
+            .ok_or_else(|| "Command interrupted".to_owned())?;
+        if let Some(case) = pred.find_case(false, &actual_code) {
+            let message = format!(
+                "Unexpected return code {}, failed {}",
+                actual_code,
+                case.tree()
+            );
+            return Err(self.explain(message, pred));
+        }
+        Ok(())
+    }
+
+    /// Ensure the command returned `expected`, as a shell would observe it in `$?`.
+    ///
+    /// Shells only see the low 8 bits of an exit code, so the actual code is masked with `0xff`
+    /// before comparing.  A command exiting with `300` matches `44`.
+    ///
+    /// # Panics
+    ///
+    /// If `expected` is outside of `0..=255`, since a shell can never observe it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("exit", "42")
+    ///     .assert()
+    ///     .code_shell(42);
+    /// ```
+    pub fn code_shell(self, expected: i32) -> Self {
+        if !(0..=0xff).contains(&expected) {
+            panic!(
+                "Invalid expected code {}, a shell only sees 0 to 255\n{}",
+                expected, self
+            );
+        }
+        let actual_code = match self.output.status.code() {
+            Some(code) => code,
+            None => panic!("Command interrupted\n{}", self),
+        };
+        let masked = actual_code & 0xff;
+        if masked != expected {
+            panic!(
+                "Unexpected return code {} (raw {}), expected {} as seen by a shell\n{}",
+                masked, actual_code, expected, self
+            );
+        }
+        self
+    }
+
+    /// Ensure the command returned the same code as `other`, like a baseline run.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// let baseline = Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("exit", "42")
+    ///     .assert();
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("exit", "42")
+    ///     .assert()
+    ///     .code_matches(&baseline);
+    /// ```
+    pub fn code_matches(self, other: &Assert) -> Self {
+        let actual = self.output.status.code();
+        let expected = other.output.status.code();
+        if actual != expected {
+            let describe = |code: Option<i32>| {
+                code.map_or_else(|| "<interrupted>".to_owned(), |code| code.to_string())
+            };
+            panic!(
+                "Unexpected return code {}, expected {} from the other run\n{}",
+                describe(actual),
+                describe(expected),
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure the command returned the same code as any of `others`, like several known-good
+    /// runs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// let found = Command::cargo_bin("bin_fixture").unwrap().assert();
+    /// let missing = Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("exit", "1")
+    ///     .assert();
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("exit", "1")
+    ///     .assert()
+    ///     .code_matches_any(&[&found, &missing]);
+    /// ```
+    pub fn code_matches_any(self, others: &[&Assert]) -> Self {
+        let actual = self.output.status.code();
+        let expected: Vec<_> = others
+            .iter()
+            .map(|other| other.output.status.code())
+            .collect();
+        if !expected.contains(&actual) {
+            let describe = |code: Option<i32>| {
+                code.map_or_else(|| "<interrupted>".to_owned(), |code| code.to_string())
+            };
+            let expected: Vec<_> = expected.into_iter().map(describe).collect();
+            panic!(
+                "Unexpected return code {}, expected any of [{}] from the other runs\n{}",
+                describe(actual),
+                expected.join(", "),
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure the command returned `errno` as its code, for tools exiting with the `errno` of
+    /// the error they hit.
+    ///
+    /// Failures describe both codes as OS errors, like `No such file or directory`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// // `ENOENT`
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("exit", "2")
+    ///     .assert()
+    ///     .code_errno(2);
+    /// ```
+    pub fn code_errno(self, errno: i32) -> Self {
+        let actual = self.output.status.code();
+        if actual != Some(errno) {
+            let describe = |code: i32| format!("{} ({})", code, io::Error::from_raw_os_error(code));
+            let actual = actual.map_or_else(|| "<interrupted>".to_owned(), describe);
+            panic!(
+                "Unexpected return code {}, expected errno {}\n{}",
+                actual,
+                describe(errno),
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure `check` accepts the output, relating the code, `stdout`, and `stderr` as needed.
+    ///
+    /// This expresses invariants spanning the whole output, failing with `check`'s message.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "a\nb")
+    ///     .env("exit", "2")
+    ///     .assert()
+    ///     .relate(|output| {
+    ///         let lines = output.stdout.split(|b| *b == b'\n').filter(|l| !l.is_empty());
+    ///         let count = lines.count() as i32;
+    ///         if output.status.code() == Some(count) {
+    ///             Ok(())
+    ///         } else {
+    ///             Err(format!("code is not the line count, {}", count))
+    ///         }
+    ///     });
+    /// ```
+    pub fn relate<F>(self, check: F) -> Self
+    where
+        F: FnOnce(&process::Output) -> Result<(), String>,
+    {
+        if let Err(err) = check(&self.output) {
+            panic!("Unexpected output, {}\n{}", err, self);
+        }
+        self
+    }
+
+    /// Ensure the command's raw wait status matches.
+    ///
+    /// This is the unnormalized value from [`ExitStatusExt::into_raw`], allowing exit codes and
+    /// signals to be distinguished precisely.
+    ///
+    /// This uses [`IntoCodePredicate`] to provide short-hands for common cases.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("exit", "42")
+    ///     .assert()
+    ///     .raw_status(42 << 8);
+    /// ```
+    ///
+    /// [`ExitStatusExt::into_raw`]: https://doc.rust-lang.org/std/os/unix/process/trait.ExitStatusExt.html#tymethod.into_raw
+    /// [`IntoCodePredicate`]: trait.IntoCodePredicate.html
+    #[cfg(unix)]
+    pub fn raw_status<I, P>(self, pred: I) -> Self
+    where
+        I: IntoCodePredicate<P>,
+        P: predicates_core::Predicate<i32>,
+    {
+        self.raw_status_impl(&pred.into_code())
+    }
+
+    #[cfg(unix)]
+    fn raw_status_impl(self, pred: &dyn predicates_core::Predicate<i32>) -> Self {
+        use std::os::unix::process::ExitStatusExt;
+
+        let actual_raw = self.output.status.into_raw();
+        if let Some(case) = pred.find_case(false, &actual_raw) {
+            let message = format!("Unexpected raw status, failed {}", case.tree());
+            panic!(
+                "{}\nraw_status={}\n{}",
+                self.explain(message, pred),
+                actual_raw,
+                self
+            );
+        }
+        self
+    }
+
+    /// How long after being spawned the command first wrote to `stdout` or `stderr`.
+    ///
+    /// Returns `None` if nothing was written.  This approximates startup latency.
+    ///
+    /// # Panics
+    ///
+    /// If the `Assert` wasn't created by running a [`Command`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// let assert = Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .assert();
+    /// println!("{:?} of {:?}", assert.spawn_to_first_byte(), assert.total());
+    /// ```
+    ///
+    /// [`Command`]: https://doc.rust-lang.org/std/process/struct.Command.html
+    pub fn spawn_to_first_byte(&self) -> Option<time::Duration> {
+        let timings = self.get_timings();
+        timings.first_output().map(|first| first - timings.spawned)
+    }
+
+    /// How long the command ran, from being spawned to exiting.
+    ///
+    /// # Panics
+    ///
+    /// If the `Assert` wasn't created by running a [`Command`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// let assert = Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .assert();
+    /// println!("{:?}", assert.total());
+    /// ```
+    ///
+    /// [`Command`]: https://doc.rust-lang.org/std/process/struct.Command.html
+    pub fn total(&self) -> time::Duration {
+        let timings = self.get_timings();
+        timings.exited - timings.spawned
+    }
+
+    fn get_timings(&self) -> &Timings {
+        self.timings
+            .as_ref()
+            .unwrap_or_else(|| panic!("Timings unavailable, run the `Command` to assert\n{}", self))
+    }
+
+    /// Ensure the command started writing to `stdout` within `duration` of being spawned.
+    ///
+    /// Only available when the `Assert` was created by running a [`Command`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    /// use std::time::Duration;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .assert()
+    ///     .first_byte_within(Duration::from_secs(5));
+    /// ```
+    ///
+    /// [`Command`]: https://doc.rust-lang.org/std/process/struct.Command.html
+    pub fn first_byte_within(self, duration: time::Duration) -> Self {
+        let elapsed = {
+            let timings = self.get_timings();
+            timings.first_stdout.map(|first| first - timings.spawned)
+        };
+        match elapsed {
+            Some(elapsed) if elapsed <= duration => {}
+            Some(elapsed) => panic!(
+                "Unexpected latency, first stdout byte after {:?}, expected within {:?}\n{}",
+                elapsed, duration, self
+            ),
+            None => panic!(
+                "Unexpected stdout, nothing written, expected within {:?}\n{}",
+                duration, self
+            ),
+        }
+        self
+    }
+
+    /// Ensure the command never went longer than `duration` without completing a line of
+    /// `stdout`, like a progress heartbeat.
+    ///
+    /// This covers the whole run: from being spawned to the first line, between lines, and from
+    /// the last line to exiting.  Lines are timed as they are read while the command runs, so a
+    /// line is only seen once the command flushes it.  Failures show the line completed after the
+    /// longest gap.  Only available when the `Assert` was created by running a [`Command`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    /// use std::time::Duration;
+    ///
+    /// Command::new("sh")
+    ///     .arg("-c")
+    ///     .arg("for i in 1 2 3; do echo progress $i; sleep 0.1; done")
+    ///     .assert()
+    ///     .max_line_interval(Duration::from_secs(1));
+    /// ```
+    ///
+    /// [`Command`]: https://doc.rust-lang.org/std/process/struct.Command.html
+    pub fn max_line_interval(self, duration: time::Duration) -> Self {
+        let timings = self.get_timings();
+        let events: Vec<_> = iter::once(timings.spawned)
+            .chain(timings.stdout_lines.iter().cloned())
+            .chain(iter::once(timings.exited))
+            .collect();
+        let stall = events
+            .windows(2)
+            .enumerate()
+            .map(|(i, pair)| (i, pair[1] - pair[0]))
+            .max_by_key(|&(_, gap)| gap);
+        if let Some((index, gap)) = stall {
+            if duration < gap {
+                let before = if index < timings.stdout_lines.len() {
+                    let text = String::from_utf8_lossy(&self.output.stdout);
+                    let line = text.lines().nth(index).unwrap_or_default();
+                    format!("before stdout line {} `{}`", index + 1, line)
+                } else {
+                    "before exiting".to_owned()
+                };
+                panic!(
+                    "Unexpected stall, {:?} {}, expected within {:?}\n{}",
+                    gap, before, duration, self
+                );
+            }
+        }
+        self
+    }
+
+    /// Ensure the command wrote the expected data to `stdout`.
+    ///
+    /// This uses [`IntoOutputPredicate`] to provide short-hands for common cases.
+    ///
+    /// See [`predicates`] for more predicates.
+    ///
+    /// # Examples
+    ///
+    /// Accepting a bytes predicate:
+    /// ```rust,no_run
+    /// extern crate assert_cmd;
+    /// extern crate predicates;
+    ///
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    /// use predicates::prelude::*;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .env("stderr", "world")
+    ///     .assert()
+    ///     .stdout(predicate::eq(b"hello\n" as &[u8]));
+    /// ```
+    ///
+    /// Accepting a `str` predicate:
+    /// ```rust,no_run
+    /// extern crate assert_cmd;
+    /// extern crate predicates;
+    ///
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    /// use predicates::prelude::*;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .env("stderr", "world")
+    ///     .assert()
+    ///     .stdout(predicate::str::similar("hello\n"));
+    /// ```
+    ///
+    /// Accepting bytes:
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .env("stderr", "world")
+    ///     .assert()
+    ///     .stdout(b"hello\n" as &[u8]);
+    /// ```
+    ///
+    /// Accepting a `str`:
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .env("stderr", "world")
+    ///     .assert()
+    ///     .stdout("hello\n");
+    /// ```
+    ///
+    /// [`predicates`]: https://docs.rs/predicates
+    /// [`IntoOutputPredicate`]: trait.IntoOutputPredicate.html
+    pub fn stdout<I, P>(self, pred: I) -> Self
+    where
+        I: IntoOutputPredicate<P>,
+        P: predicates_core::Predicate<[u8]>,
+    {
+        self.stdout_impl(&pred.into_output())
+    }
+
+    /// Ensure the command wrote the expected data to `stdout`, decoded from UTF-16LE.
+    ///
+    /// Some Windows tools write UTF-16LE, e.g. to certain handles.  A leading byte order mark
+    /// is skipped and `pred` is applied to the decoded text, which failures show.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("cmd")
+    ///     .args(["/U", "/C", "echo hello"])
+    ///     .assert()
+    ///     .stdout_utf16("hello\r\n");
+    /// ```
+    #[cfg(windows)]
+    pub fn stdout_utf16<I, P>(self, pred: I) -> Self
+    where
+        I: IntoOutputPredicate<P>,
+        P: predicates_core::Predicate<[u8]>,
+    {
+        let decoded = match decode_utf16le(&self.output.stdout) {
+            Ok(decoded) => decoded,
+            Err(offset) => panic!(
+                "Unexpected stdout, invalid UTF-16 at offset {}\n{}",
+                offset, self
+            ),
+        };
+        let pred = pred.into_output();
+        if let Some(case) = pred.find_case(false, decoded.as_bytes()) {
+            let message = format!("Unexpected stdout, failed {}", case.tree());
+            panic!(
+                "{}decoded=```{}```\n{}",
+                self.explain(message, &pred),
+                decoded,
+                self
+            );
+        }
+        self
+    }
+
+    fn stdout_impl(self, pred: &predicates_core::Predicate<[u8]>) -> Self {
+        if let Err(err) = self.check_stdout(pred) {
+            panic!("{}\n{}", err, self);
+        }
+        self
+    }
+
+    fn check_stdout(&self, pred: &dyn predicates_core::Predicate<[u8]>) -> Result<(), String> {
+        let actual = &self.output.stdout;
+        if let Some(case) = pred.find_case(false, &actual) {
+            let message = format!("Unexpected stdout, failed {}", case.tree());
+            return Err(self.explain(message, pred));
+        }
+        Ok(())
+    }
+
+    /// Ensure the bytes of the command's `stdout` within `range` match `pred`.
+    ///
+    /// This checks fixed-offset fields of binary output, like a magic number, without comparing
+    /// the rest.  See [`Assert::stdout`] for the accepted predicates.  Failures show the bytes in
+    /// hex.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("printf")
+    ///     .arg("\\177ELF\\002")
+    ///     .assert()
+    ///     .stdout_range(0..4, b"\x7fELF" as &[u8]);
+    /// ```
+    ///
+    /// [`Assert::stdout`]: #method.stdout
+    pub fn stdout_range<I, P>(self, range: ops::Range<usize>, pred: I) -> Self
+    where
+        I: IntoOutputPredicate<P>,
+        P: predicates_core::Predicate<[u8]>,
+    {
+        let len = self.output.stdout.len();
+        let actual = match self.output.stdout.get(range.clone()) {
+            Some(actual) => actual,
+            None => panic!(
+                "Unexpected stdout, range {:?} is out of bounds of {} bytes\n{}",
+                range, len, self
+            ),
+        };
+        let pred = pred.into_output();
+        if let Some(case) = pred.find_case(false, actual) {
+            let message = format!(
+                "Unexpected stdout, bytes {:?} = [{}] failed {}",
+                range,
+                hex(actual),
+                case.tree()
+            );
+            panic!("{}\n{}", self.explain(message, &pred), self);
+        }
+        self
+    }
+
+    /// Ensure the command wrote the same data to `stdout` as can be read from `reader`.
+    ///
+    /// `reader` is compared incrementally, without buffering all of it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::fs;
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .env("stderr", "world")
+    ///     .assert()
+    ///     .stdout_eq_reader(fs::File::open("tests/fixtures/hello.txt").unwrap());
+    /// ```
+    pub fn stdout_eq_reader<R>(self, reader: R) -> Self
+    where
+        R: io::Read,
+    {
+        let offset = first_difference(&self.output.stdout, reader);
+        match offset {
+            Ok(None) => {}
+            Ok(Some(offset)) => panic!(
+                "Unexpected stdout, differs from reader at offset {}\n{}",
+                offset, self
+            ),
+            Err(err) => panic!("Failed to read expected stdout: {}\n{}", err, self),
+        }
+        self
+    }
+
+    /// Ensure the command wrote the content of one of `paths` to `stdout`.
+    ///
+    /// This is useful when the expected output legitimately varies, like by platform.  On
+    /// failure, the differences with the closest file, by lines changed, are shown.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .assert()
+    ///     .stdout_eq_any_path(&["tests/fixtures/world.txt", "tests/fixtures/hello.txt"]);
+    /// ```
+    pub fn stdout_eq_any_path<P>(self, paths: &[P]) -> Self
+    where
+        P: AsRef<path::Path>,
+    {
+        let closest = {
+            let actual = &self.output.stdout;
+            let mut closest: Option<(usize, &path::Path, Vec<u8>)> = None;
+            for path in paths {
+                let path = path.as_ref();
+                let expected = fs::read(path).unwrap_or_else(|err| {
+                    panic!("Failed to read {}: {}\n{}", path.display(), err, self)
+                });
+                if expected == *actual {
+                    return self;
+                }
+                let distance = edit_distance(
+                    &expected.split(|b| *b == b'\n').collect::<Vec<_>>(),
+                    &actual.split(|b| *b == b'\n').collect::<Vec<_>>(),
+                );
+                let is_closer = match closest {
+                    Some((closest_distance, _, _)) => distance < closest_distance,
+                    None => true,
+                };
+                if is_closer {
+                    closest = Some((distance, path, expected));
+                }
+            }
+            closest.map(|(_, path, expected)| {
+                (path.display().to_string(), diff_buffers(&expected, actual))
+            })
+        };
+        let (path, diff) = closest.expect("At least one path is required");
+        panic!(
+            "Unexpected stdout, matched none of {} file(s), closest is {}\n{}\n{}",
+            paths.len(),
+            path,
+            diff,
+            self
+        );
+    }
+
+    /// Ensure the command wrote `expected` to `stdout`, once `prefix` is stripped from each line.
+    ///
+    /// `prefix` is a regex matched at the start of each line, useful for ignoring timestamps or
+    /// log levels.  Requires the `regex` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "[12:00:01] INFO hello")
+    ///     .assert()
+    ///     .stdout_ignoring_prefix(r"\[[0-9:]+\] \w+ ", "hello\n");
+    /// ```
+    #[cfg(feature = "regex")]
+    pub fn stdout_ignoring_prefix(self, prefix: &str, expected: &str) -> Self {
+        let pred = StripPrefixPredicate::new(prefix, predicates::str::similar(expected.to_owned()));
+        self.stdout_impl(&pred)
+    }
+
+    /// Ensure the command only wrote printable ASCII, newlines, and tabs to `stdout`.
+    ///
+    /// Unlike UTF-8 validation, this catches leaked control characters, like color codes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello\tworld")
+    ///     .assert()
+    ///     .stdout_is_ascii();
+    /// ```
+    pub fn stdout_is_ascii(self) -> Self {
+        self.stdout_is_ascii_allowing(DEFAULT_CONTROLS)
+    }
+
+    /// Ensure the command only wrote printable ASCII and the `controls` characters to `stdout`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello\tworld")
+    ///     .assert()
+    ///     .stdout_is_ascii_allowing(b"\r\n\t");
+    /// ```
+    pub fn stdout_is_ascii_allowing(self, controls: &[u8]) -> Self {
+        if let Some(offset) = find_non_ascii(&self.output.stdout, controls) {
+            panic!(
+                "Unexpected stdout, disallowed byte {:#04x} at offset {}\n{}",
+                self.output.stdout[offset], offset, self
+            );
+        }
+        self
+    }
+
+    /// Ensure the JSON the command wrote to `stdout` has `expected` at `path`.
+    ///
+    /// `path` is a small subset of JSONPath: dotted keys and array indices, like `.items[0].name`.
+    ///
+    /// Requires the `json` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// extern crate assert_cmd;
+    /// extern crate serde_json;
+    ///
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", r#"{"version": "1.2.3"}"#)
+    ///     .assert()
+    ///     .stdout_json_field(".version", serde_json::json!("1.2.3"));
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn stdout_json_field(self, path: &str, expected: serde_json::Value) -> Self {
+        let actual: serde_json::Value = match serde_json::from_slice(&self.output.stdout) {
+            Ok(actual) => actual,
+            Err(err) => panic!("Unexpected stdout, invalid JSON: {}\n{}", err, self),
+        };
+        let found = match json::resolve(&actual, path) {
+            Ok(found) => found,
+            Err(err) => panic!("{}\n{}", err, self),
+        };
+        if found != Some(&expected) {
+            let found = found.map_or_else(|| "not present".to_owned(), |found| found.to_string());
+            panic!(
+                "Unexpected stdout, JSON field `{}` is {}, expected {}\n{}",
+                path, found, expected, self
+            );
+        }
+        self
+    }
+
+    /// Ensure the JSON the command wrote to `stdout` is valid against the [JSON Schema] `schema`.
+    ///
+    /// See [`predicate::json_schema`].  Requires the `jsonschema` feature, which needs Rust 1.88.
+    ///
+    /// # Panics
+    ///
+    /// If `schema` is not a valid JSON Schema.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", r#"{"version": "1.2.3"}"#)
+    ///     .assert()
+    ///     .stdout_json_schema(r#"{"type": "object", "required": ["version"]}"#);
+    /// ```
+    ///
+    /// [JSON Schema]: https://json-schema.org/
+    /// [`predicate::json_schema`]: ../predicate/fn.json_schema.html
+    #[cfg(feature = "jsonschema")]
+    pub fn stdout_json_schema(self, schema: &str) -> Self {
+        self.stdout_impl(&json_schema(schema))
+    }
+
+    /// Ensure the TOML the command wrote to `stdout` is structurally equal to `expected`.
+    ///
+    /// See [`predicate::toml_eq`].  Requires the `toml` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// extern crate assert_cmd;
+    /// extern crate toml;
+    ///
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "name   =   'bin'")
+    ///     .assert()
+    ///     .stdout_toml(toml::from_str("name = \"bin\"").unwrap());
+    /// ```
+    ///
+    /// [`predicate::toml_eq`]: ../predicate/fn.toml_eq.html
+    #[cfg(feature = "toml")]
+    pub fn stdout_toml(self, expected: toml::Value) -> Self {
+        self.stdout_impl(&toml_eq(expected))
+    }
+
+    /// Ensure the YAML the command wrote to `stdout` is structurally equal to `expected`.
+    ///
+    /// See [`predicate::yaml_eq`].  Requires the `yaml` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// extern crate assert_cmd;
+    /// extern crate serde_yaml;
+    ///
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "{name: 'bin'}")
+    ///     .assert()
+    ///     .stdout_yaml(serde_yaml::from_str("name: bin").unwrap());
+    /// ```
+    ///
+    /// [`predicate::yaml_eq`]: ../predicate/fn.yaml_eq.html
+    #[cfg(feature = "yaml")]
+    pub fn stdout_yaml(self, expected: serde_yaml::Value) -> Self {
+        self.stdout_impl(&yaml_eq(expected))
+    }
+
+    /// Ensure the CSV the command wrote to `stdout` has the `expected` header row.
+    ///
+    /// Every row must also have as many columns as the header.
+    ///
+    /// Requires the `csv` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "name,size\nhello.txt,5")
+    ///     .assert()
+    ///     .stdout_csv_headers(&["name", "size"]);
+    /// ```
+    #[cfg(feature = "csv")]
+    pub fn stdout_csv_headers(self, expected: &[&str]) -> Self {
+        let (headers, _) = match read_csv(&self.output.stdout) {
+            Ok(read) => read,
+            Err(err) => panic!("Unexpected stdout, invalid CSV: {}\n{}", err, self),
+        };
+        if headers != expected {
+            panic!(
+                "Unexpected stdout, CSV headers are {:?}, expected {:?}\n{}",
+                headers, expected, self
+            );
+        }
+        self
+    }
+
+    /// Ensure the number of rows, after the header, in the CSV the command wrote to `stdout`
+    /// matches `pred`.
+    ///
+    /// Every row must also have as many columns as the header.
+    ///
+    /// Requires the `csv` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// extern crate assert_cmd;
+    /// extern crate predicates;
+    ///
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use predicates::prelude::*;
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "name,size\nhello.txt,5\nworld.txt,5")
+    ///     .assert()
+    ///     .stdout_csv_rows(predicate::eq(2));
+    /// ```
+    #[cfg(feature = "csv")]
+    pub fn stdout_csv_rows<P>(self, pred: P) -> Self
+    where
+        P: predicates_core::Predicate<usize>,
+    {
+        let (_, rows) = match read_csv(&self.output.stdout) {
+            Ok(read) => read,
+            Err(err) => panic!("Unexpected stdout, invalid CSV: {}\n{}", err, self),
+        };
+        if let Some(case) = pred.find_case(false, &rows) {
+            let message = format!("Unexpected stdout, CSV rows failed {}", case.tree());
+            panic!("{}\n{}", self.explain(message, &pred), self);
+        }
+        self
+    }
+
+    /// Ensure the command wrote `expected` to `stdout`, after removing its indentation.
+    ///
+    /// This keeps multi-line expectations readable when written inline.  The indentation is
+    /// removed like the `indoc` crate does:
+    ///
+    /// - A first line that is empty, like after an opening quote, is removed.
+    /// - Lines with only whitespace, like before a closing quote, are emptied.
+    /// - The indentation is the fewest leading spaces or tabs on the remaining lines, which is
+    ///   removed from each of them.
+    ///
+    /// Failures show the de-indented `expected`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello\n  world")
+    ///     .assert()
+    ///     .stdout_indoc(
+    ///         "
+    ///         hello
+    ///           world
+    ///         ",
+    ///     );
+    /// ```
+    pub fn stdout_indoc(self, expected: &str) -> Self {
+        self.stdout_impl(&predicates::str::similar(dedent(expected)).from_utf8())
+    }
+
+    /// Ensure the command's `stdout` starts with `prefix`.
+    ///
+    /// Failures show as much of the start of `stdout` as `prefix` is long.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello world")
+    ///     .assert()
+    ///     .stdout_starts_with("hello");
+    /// ```
+    pub fn stdout_starts_with<S>(self, prefix: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let prefix = prefix.into();
+        let width = prefix.chars().count();
+        let pred = predicates::str::starts_with(prefix).from_utf8();
+        if let Err(err) = self.check_stdout(&pred) {
+            let actual = String::from_utf8_lossy(&self.output.stdout);
+            panic!(
+                "{}actual start=```{}```\n{}",
+                err,
+                leading(&actual, width),
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure the command's `stdout` ends with `suffix`.
+    ///
+    /// Failures show as much of the end of `stdout` as `suffix` is long.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello world")
+    ///     .assert()
+    ///     .stdout_ends_with("world\n");
+    /// ```
+    pub fn stdout_ends_with<S>(self, suffix: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let suffix = suffix.into();
+        let width = suffix.chars().count();
+        let pred = predicates::str::ends_with(suffix).from_utf8();
+        if let Err(err) = self.check_stdout(&pred) {
+            let actual = String::from_utf8_lossy(&self.output.stdout);
+            panic!(
+                "{}actual end=```{}```\n{}",
+                err,
+                trailing(&actual, width),
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure the last `n` lines of the command's `stdout` match `pred`, e.g. a trailing summary.
+    ///
+    /// `stdout` is decoded, lossily, and `pred` is given its last `n` lines, including their line
+    /// endings.  Unlike [`stdout_ends_with`], this ignores how long the summary lines are.
+    /// Failures show the tail.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// extern crate assert_cmd;
+    /// extern crate predicates;
+    ///
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    /// use predicates::prelude::*;
+    ///
+    /// Command::new("sh")
+    ///     .arg("-c")
+    ///     .arg("echo compiling; echo compiling; echo 2 passed; echo 0 failed")
+    ///     .assert()
+    ///     .stdout_tail(2, predicate::str::contains("0 failed"));
+    /// ```
+    ///
+    /// [`stdout_ends_with`]: #method.stdout_ends_with
+    pub fn stdout_tail<P>(self, n: usize, pred: P) -> Self
+    where
+        P: predicates_core::Predicate<str>,
+    {
+        let tail = {
+            let stdout = String::from_utf8_lossy(&self.output.stdout);
+            let lines: Vec<_> = stdout.split_inclusive('\n').collect();
+            lines[lines.len().saturating_sub(n)..].concat()
+        };
+        if let Some(case) = pred.find_case(false, &tail) {
+            let message = format!("Unexpected stdout, last {} lines failed {}", n, case.tree());
+            panic!(
+                "{}tail=```{}```\n{}",
+                self.explain(message, &pred),
+                tail,
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure the command wrote `expected` to `stdout`, once `replacements` are applied to it.
+    ///
+    /// Each `(pattern, replacement)` replaces matches of the regex `pattern`, in order, like for
+    /// [`snapshot_filter`].  This keeps comparisons stable when the output includes run-specific
+    /// values, like temporary paths.  Failures show the normalized `stdout`.  Requires the `regex`
+    /// feature.
+    ///
+    /// # Panics
+    ///
+    /// If a `pattern` is not a valid regex.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "wrote /tmp/.tmpA1b2C3/out.txt")
+    ///     .assert()
+    ///     .stdout_normalizing(&[(r"/tmp/\.tmp\w+", "<TMP>")], "wrote <TMP>/out.txt\n");
+    /// ```
+    ///
+    /// [`snapshot_filter`]: #method.snapshot_filter
+    #[cfg(feature = "regex")]
+    pub fn stdout_normalizing(self, replacements: &[(&str, &str)], expected: &str) -> Self {
+        let normalized = normalize(&self.output.stdout, replacements);
+        if normalized != expected {
+            panic!(
+                "Unexpected stdout, after normalizing\nnormalized=```{}```\n{}\n{}",
+                normalized,
+                diff_buffers(expected.as_bytes(), normalized.as_bytes()),
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure `stdout` has each of the `required` section headers, like the `USAGE` and `OPTIONS`
+    /// of a help message, in order.
+    ///
+    /// A header is a line whose text, up to any `:`, matches the section ignoring case and
+    /// surrounding whitespace, so `USAGE:`, `Usage: prog [OPTIONS]` and `Options:` all count.
+    /// The first header for each section is used.  Failures show the line where each was found.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("sh")
+    ///     .arg("-c")
+    ///     .arg("printf 'USAGE:\\n    prog [OPTIONS]\\n\\nOPTIONS:\\n    -h, --help\\n'")
+    ///     .assert()
+    ///     .assert_help_sections(&["USAGE", "OPTIONS"]);
+    /// ```
+    pub fn assert_help_sections(self, required: &[&str]) -> Self {
+        let positions: Vec<_> = {
+            let stdout = String::from_utf8_lossy(&self.output.stdout);
+            required
+                .iter()
+                .map(|section| {
+                    stdout
+                        .lines()
+                        .position(|line| is_section_header(line, section))
+                })
+                .collect()
+        };
+        let ordered = positions.iter().all(Option::is_some)
+            && positions.windows(2).all(|pair| pair[0] < pair[1]);
+        if !ordered {
+            let found: Vec<_> = required
+                .iter()
+                .zip(&positions)
+                .map(|(section, position)| match *position {
+                    Some(position) => format!("`{}` at line {}", section, position + 1),
+                    None => format!("`{}` missing", section),
+                })
+                .collect();
+            panic!(
+                "Unexpected help sections, found {}\n{}",
+                found.join(", "),
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure the text finally displayed from `stdout` is `expected`, after carriage-return
+    /// updates like a progress bar.
+    ///
+    /// Each line is split on `\r`, keeping the last non-empty segment, as the final update
+    /// overwrites the earlier ones.  Failures show the reconstructed text.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("sh")
+    ///     .arg("-c")
+    ///     .arg("printf ' 50%%\\r100%%\\ndone\\n'")
+    ///     .assert()
+    ///     .stdout_final_line("100%\ndone\n");
+    /// ```
+    pub fn stdout_final_line(self, expected: &str) -> Self {
+        let displayed = final_display(&String::from_utf8_lossy(&self.output.stdout));
+        if displayed != expected {
+            panic!(
+                "Unexpected stdout, after carriage returns\ndisplayed=```{}```\n{}\n{}",
+                displayed,
+                diff_buffers(expected.as_bytes(), displayed.as_bytes()),
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure the command wrote `expected` to `stdout`, once a trailing `trailer` is removed.
+    ///
+    /// This ignores a trailing prompt, like `> ` from a REPL, when present.  Failures note whether
+    /// `trailer` was found and removed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("sh")
+    ///     .arg("-c")
+    ///     .arg("printf 'hello\\n> '")
+    ///     .assert()
+    ///     .stdout_without_trailing("> ", "hello\n");
+    /// ```
+    pub fn stdout_without_trailing(self, trailer: &str, expected: &str) -> Self {
+        let (actual, removed) = strip_trailer(&self.output.stdout, trailer);
+        if actual != expected {
+            panic!(
+                "Unexpected stdout, with trailer `{}` {}\n{}\n{}",
+                trailer,
+                if removed { "removed" } else { "not found" },
+                diff_buffers(expected.as_bytes(), actual.as_bytes()),
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure the command wrote `expected` to `stdout`, allowing numbers to differ by `epsilon`.
+    ///
+    /// Both are split into whitespace-separated tokens.  Tokens that are both numbers are
+    /// compared within `epsilon`, other tokens must match exactly.  This tolerates floating-point
+    /// results differing in their last digits across platforms.  Failures point at the first
+    /// token that differs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "mean: 0.30000000000000004")
+    ///     .assert()
+    ///     .stdout_numeric_tolerant("mean: 0.3", 1e-9);
+    /// ```
+    pub fn stdout_numeric_tolerant(self, expected: &str, epsilon: f64) -> Self {
+        let actual = String::from_utf8_lossy(&self.output.stdout);
+        if let Err(err) = numeric_tolerant_eq(&actual, expected, epsilon) {
+            panic!("Unexpected stdout, {}\n{}", err, self);
+        }
+        self
+    }
+
+    /// Ensure the command's `stdout` starts with a UTF-8 or UTF-16 byte-order mark.
+    ///
+    /// Failures show the leading bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("printf")
+    ///     .arg("\\357\\273\\277name,count\\n")
+    ///     .assert()
+    ///     .stdout_has_bom();
+    /// ```
+    pub fn stdout_has_bom(self) -> Self {
+        if bom(&self.output.stdout).is_none() {
+            panic!(
+                "Unexpected stdout, no byte-order mark, starts with [{}]\n{}",
+                hex(leading_bytes(&self.output.stdout)),
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure the command's `stdout` doesn't start with a UTF-8 or UTF-16 byte-order mark.
+    ///
+    /// Failures show which byte-order mark was found.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "name,count")
+    ///     .assert()
+    ///     .stdout_no_bom();
+    /// ```
+    pub fn stdout_no_bom(self) -> Self {
+        if let Some((encoding, mark)) = bom(&self.output.stdout) {
+            panic!(
+                "Unexpected stdout, starts with {} byte-order mark [{}]\n{}",
+                encoding,
+                hex(mark),
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure the command's `stdout` is within `max` edits of `expected`.
+    ///
+    /// Edits are character insertions, deletions, and substitutions, i.e. the Levenshtein
+    /// distance.  This tolerates small, expected differences, like version numbers.  Failures
+    /// show the distance and the differences.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "tool 1.2.4")
+    ///     .assert()
+    ///     .stdout_within_distance("tool 1.2.3\n", 1);
+    /// ```
+    pub fn stdout_within_distance(self, expected: &str, max: usize) -> Self {
+        let actual = String::from_utf8_lossy(&self.output.stdout).into_owned();
+        let distance = edit_distance(
+            &expected.chars().collect::<Vec<_>>(),
+            &actual.chars().collect::<Vec<_>>(),
+        );
+        if max < distance {
+            panic!(
+                "Unexpected stdout, {} edits from expected, more than {}\n{}\n{}",
+                distance,
+                max,
+                diff_buffers(expected.as_bytes(), actual.as_bytes()),
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure the command wrote the lines of `expected` to `stdout`, in any order.
+    ///
+    /// The lines of both are sorted before comparing, so each line must appear as many times as
+    /// in `expected`, with no others.  Failures show the differences between the sorted lines.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "b\na\nb")
+    ///     .assert()
+    ///     .stdout_sorted_eq("a\nb\nb\n");
+    /// ```
+    pub fn stdout_sorted_eq(self, expected: &str) -> Self {
+        let actual = sorted_lines(&String::from_utf8_lossy(&self.output.stdout));
+        let expected = sorted_lines(expected);
+        if actual != expected {
+            panic!(
+                "Unexpected stdout, sorted lines differ\n{}\n{}",
+                diff_buffers(expected.as_bytes(), actual.as_bytes()),
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure `needle` appears exactly `count` times in the command's `stdout`.
+    ///
+    /// Occurrences are counted without overlapping.  Failures show the actual count.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "warning: a\nwarning: b\nwarning: c")
+    ///     .assert()
+    ///     .stdout_contains_count("warning:", 3);
+    /// ```
+    pub fn stdout_contains_count(self, needle: &str, count: usize) -> Self {
+        self.stdout_contains_count_matching(needle, predicates::ord::eq(count))
+    }
+
+    /// Ensure the number of times `needle` appears in the command's `stdout` matches `pred`.
+    ///
+    /// Occurrences are counted without overlapping.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// extern crate assert_cmd;
+    /// extern crate predicates;
+    ///
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use predicates::prelude::*;
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "warning: a\nwarning: b\nwarning: c")
+    ///     .assert()
+    ///     .stdout_contains_count_matching("warning:", predicate::ge(2));
+    /// ```
+    pub fn stdout_contains_count_matching<P>(self, needle: &str, pred: P) -> Self
+    where
+        P: predicates_core::Predicate<usize>,
+    {
+        let count = String::from_utf8_lossy(&self.output.stdout)
+            .matches(needle)
+            .count();
+        if let Some(case) = pred.find_case(false, &count) {
+            let message = format!(
+                "Unexpected stdout, `{}` appears {} times, failed {}",
+                needle,
+                count,
+                case.tree()
+            );
+            panic!("{}\n{}", self.explain(message, &pred), self);
+        }
+        self
+    }
+
+    /// Ensure `parser` accepts what the command wrote to `stdout`.
+    ///
+    /// This validates custom formats without writing a [Predicate].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    /// use std::str;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "42")
+    ///     .assert()
+    ///     .stdout_parses_with(|stdout| {
+    ///         str::from_utf8(stdout)
+    ///             .map_err(|e| e.to_string())
+    ///             .and_then(|s| s.trim().parse::<u32>().map_err(|e| e.to_string()))
+    ///     });
+    /// ```
+    ///
+    /// [Predicate]: https://docs.rs/predicates-core/1.0.0/predicates_core/trait.Predicate.html
+    pub fn stdout_parses_with<T, E, F>(self, parser: F) -> Self
+    where
+        F: FnOnce(&[u8]) -> Result<T, E>,
+        E: fmt::Display,
+    {
+        if let Err(err) = parser(&self.output.stdout) {
+            panic!("Unexpected stdout, failed to parse: {}\n{}", err, self);
+        }
+        self
+    }
+
+    /// Ensure the command wrote exactly one of `candidates` to `stdout`.
+    ///
+    /// Unlike a substring check, the whole of `stdout` must equal a candidate.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hi")
+    ///     .assert()
+    ///     .stdout_one_of(&["hello\n", "hi\n"]);
+    /// ```
+    pub fn stdout_one_of(self, candidates: &[&str]) -> Self {
+        if let Err(err) = one_of(&self.output.stdout, candidates) {
+            panic!("Unexpected stdout, {}\n{}", err, self);
+        }
+        self
+    }
+
+    /// Ensure the command did not write exactly `value` to `stdout`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .env("stderr", "world")
+    ///     .assert()
+    ///     .stdout_is_not("goodbye\n");
+    /// ```
+    pub fn stdout_is_not<V>(self, value: V) -> Self
+    where
+        V: Into<Vec<u8>>,
+    {
+        if self.output.stdout == value.into() {
+            panic!(
+                "Unexpected stdout, unexpectedly matched excluded value\n{}",
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure the command's `stdout` differs from the content of `path`, e.g. a baseline.
+    ///
+    /// This guards against changes that unexpectedly have no effect.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "goodbye")
+    ///     .assert()
+    ///     .stdout_differs_from_path("tests/fixtures/hello.txt");
+    /// ```
+    pub fn stdout_differs_from_path<P>(self, path: P) -> Self
+    where
+        P: AsRef<path::Path>,
+    {
+        let path = path.as_ref();
+        let baseline = fs::read(path)
+            .unwrap_or_else(|err| panic!("Failed to read {}: {}\n{}", path.display(), err, self));
+        if self.output.stdout == baseline {
+            panic!(
+                "Unexpected stdout, output unexpectedly unchanged from {}\n{}",
+                path.display(),
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure the command wrote the expected data to `stdout`, if it succeeded.
+    ///
+    /// A failed command passes regardless of `stdout`.  See [`Assert::stdout`] for the accepted
+    /// predicates.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .assert()
+    ///     .on_success_stdout("hello\n")
+    ///     .on_failure_stderr("error\n");
+    /// ```
+    ///
+    /// [`Assert::stdout`]: #method.stdout
+    pub fn on_success_stdout<I, P>(self, pred: I) -> Self
+    where
+        I: IntoOutputPredicate<P>,
+        P: predicates_core::Predicate<[u8]>,
+    {
+        if self.output.status.success() {
+            self.stdout_impl(&pred.into_output())
+        } else {
+            self
+        }
+    }
+
+    /// Ensure the command wrote the expected data to `stderr`.
+    ///
+    /// This uses [`IntoOutputPredicate`] to provide short-hands for common cases.
+    ///
+    /// See [`predicates`] for more predicates.
+    ///
+    /// # Examples
+    ///
+    /// Accepting a bytes predicate:
+    /// ```rust,no_run
+    /// extern crate assert_cmd;
+    /// extern crate predicates;
+    ///
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    /// use predicates::prelude::*;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .env("stderr", "world")
+    ///     .assert()
+    ///     .stderr(predicate::eq(b"world\n" as &[u8]));
+    /// ```
+    ///
+    /// Accepting a `str` predicate:
+    /// ```rust,no_run
+    /// extern crate assert_cmd;
+    /// extern crate predicates;
+    ///
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    /// use predicates::prelude::*;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .env("stderr", "world")
+    ///     .assert()
+    ///     .stderr(predicate::str::similar("world\n"));
+    /// ```
+    ///
+    /// Accepting bytes:
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .env("stderr", "world")
+    ///     .assert()
+    ///     .stderr(b"world\n" as &[u8]);
+    /// ```
+    ///
+    /// Accepting a `str`:
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .env("stderr", "world")
+    ///     .assert()
+    ///     .stderr("world\n");
+    /// ```
+    ///
+    /// [`predicates`]: https://docs.rs/predicates
+    /// [`IntoOutputPredicate`]: trait.IntoOutputPredicate.html
+    pub fn stderr<I, P>(self, pred: I) -> Self
+    where
+        I: IntoOutputPredicate<P>,
+        P: predicates_core::Predicate<[u8]>,
+    {
+        self.stderr_impl(&pred.into_output())
+    }
+
+    fn stderr_impl(self, pred: &predicates_core::Predicate<[u8]>) -> Self {
+        if let Err(err) = self.check_stderr(pred) {
+            panic!("{}\n\n{}", err, self);
+        }
+        self
+    }
+
+    fn check_stderr(&self, pred: &dyn predicates_core::Predicate<[u8]>) -> Result<(), String> {
+        let actual = &self.output.stderr;
+        if let Some(case) = pred.find_case(false, &actual) {
+            let message = format!("Unexpected stderr, failed {}", case.tree());
+            return Err(self.explain(message, pred));
+        }
+        Ok(())
+    }
+
+    /// Ensure the command wrote `expected` to `stderr`, once `prefix` is stripped from each line.
+    ///
+    /// `prefix` is a regex matched at the start of each line, useful for ignoring timestamps or
+    /// log levels.  Requires the `regex` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stderr", "[12:00:01] WARN world")
+    ///     .assert()
+    ///     .stderr_ignoring_prefix(r"\[[0-9:]+\] \w+ ", "world\n");
+    /// ```
+    #[cfg(feature = "regex")]
+    pub fn stderr_ignoring_prefix(self, prefix: &str, expected: &str) -> Self {
+        let pred = StripPrefixPredicate::new(prefix, predicates::str::similar(expected.to_owned()));
+        self.stderr_impl(&pred)
+    }
+
+    /// Ensure the command only wrote printable ASCII, newlines, and tabs to `stderr`.
+    ///
+    /// Unlike UTF-8 validation, this catches leaked control characters, like color codes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stderr", "hello\tworld")
+    ///     .assert()
+    ///     .stderr_is_ascii();
+    /// ```
+    pub fn stderr_is_ascii(self) -> Self {
+        self.stderr_is_ascii_allowing(DEFAULT_CONTROLS)
+    }
+
+    /// Ensure the command only wrote printable ASCII and the `controls` characters to `stderr`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stderr", "hello\tworld")
+    ///     .assert()
+    ///     .stderr_is_ascii_allowing(b"\r\n\t");
+    /// ```
+    pub fn stderr_is_ascii_allowing(self, controls: &[u8]) -> Self {
+        if let Some(offset) = find_non_ascii(&self.output.stderr, controls) {
+            panic!(
+                "Unexpected stderr, disallowed byte {:#04x} at offset {}\n{}",
+                self.output.stderr[offset], offset, self
+            );
+        }
+        self
+    }
+
+    /// Ensure the command wrote `expected` to `stderr`, after removing its indentation.
+    ///
+    /// This keeps multi-line expectations readable when written inline.  The indentation is
+    /// removed like the `indoc` crate does:
+    ///
+    /// - A first line that is empty, like after an opening quote, is removed.
+    /// - Lines with only whitespace, like before a closing quote, are emptied.
+    /// - The indentation is the fewest leading spaces or tabs on the remaining lines, which is
+    ///   removed from each of them.
+    ///
+    /// Failures show the de-indented `expected`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stderr", "hello\n  world")
+    ///     .assert()
+    ///     .stderr_indoc(
+    ///         "
+    ///         hello
+    ///           world
+    ///         ",
+    ///     );
+    /// ```
+    pub fn stderr_indoc(self, expected: &str) -> Self {
+        self.stderr_impl(&predicates::str::similar(dedent(expected)).from_utf8())
+    }
+
+    /// Ensure the command's `stderr` starts with `prefix`.
+    ///
+    /// Failures show as much of the start of `stderr` as `prefix` is long.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stderr", "hello world")
+    ///     .assert()
+    ///     .stderr_starts_with("hello");
+    /// ```
+    pub fn stderr_starts_with<S>(self, prefix: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let prefix = prefix.into();
+        let width = prefix.chars().count();
+        let pred = predicates::str::starts_with(prefix).from_utf8();
+        if let Err(err) = self.check_stderr(&pred) {
+            let actual = String::from_utf8_lossy(&self.output.stderr);
+            panic!(
+                "{}actual start=```{}```\n{}",
+                err,
+                leading(&actual, width),
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure the command's `stderr` ends with `suffix`.
+    ///
+    /// Failures show as much of the end of `stderr` as `suffix` is long.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stderr", "hello world")
+    ///     .assert()
+    ///     .stderr_ends_with("world\n");
+    /// ```
+    pub fn stderr_ends_with<S>(self, suffix: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let suffix = suffix.into();
+        let width = suffix.chars().count();
+        let pred = predicates::str::ends_with(suffix).from_utf8();
+        if let Err(err) = self.check_stderr(&pred) {
+            let actual = String::from_utf8_lossy(&self.output.stderr);
+            panic!(
+                "{}actual end=```{}```\n{}",
+                err,
+                trailing(&actual, width),
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure the command wrote `expected` to `stderr`, once `replacements` are applied to it.
+    ///
+    /// Each `(pattern, replacement)` replaces matches of the regex `pattern`, in order, like for
+    /// [`snapshot_filter`].  This keeps comparisons stable when the output includes run-specific
+    /// values, like temporary paths.  Failures show the normalized `stderr`.  Requires the `regex`
+    /// feature.
+    ///
+    /// # Panics
+    ///
+    /// If a `pattern` is not a valid regex.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stderr", "wrote /tmp/.tmpA1b2C3/out.txt")
+    ///     .assert()
+    ///     .stderr_normalizing(&[(r"/tmp/\.tmp\w+", "<TMP>")], "wrote <TMP>/out.txt\n");
+    /// ```
+    ///
+    /// [`snapshot_filter`]: #method.snapshot_filter
+    #[cfg(feature = "regex")]
+    pub fn stderr_normalizing(self, replacements: &[(&str, &str)], expected: &str) -> Self {
+        let normalized = normalize(&self.output.stderr, replacements);
+        if normalized != expected {
+            panic!(
+                "Unexpected stderr, after normalizing\nnormalized=```{}```\n{}\n{}",
+                normalized,
+                diff_buffers(expected.as_bytes(), normalized.as_bytes()),
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure the text finally displayed from `stderr` is `expected`, after carriage-return
+    /// updates like a progress bar.
+    ///
+    /// Each line is split on `\r`, keeping the last non-empty segment, as the final update
+    /// overwrites the earlier ones.  Failures show the reconstructed text.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("sh")
+    ///     .arg("-c")
+    ///     .arg("printf ' 50%%\\r100%%\\ndone\\n' >&2")
+    ///     .assert()
+    ///     .stderr_final_line("100%\ndone\n");
+    /// ```
+    pub fn stderr_final_line(self, expected: &str) -> Self {
+        let displayed = final_display(&String::from_utf8_lossy(&self.output.stderr));
+        if displayed != expected {
+            panic!(
+                "Unexpected stderr, after carriage returns\ndisplayed=```{}```\n{}\n{}",
+                displayed,
+                diff_buffers(expected.as_bytes(), displayed.as_bytes()),
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure the command wrote `expected` to `stderr`, once a trailing `trailer` is removed.
+    ///
+    /// This ignores a trailing prompt, like `> ` from a REPL, when present.  Failures note whether
+    /// `trailer` was found and removed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("sh")
+    ///     .arg("-c")
+    ///     .arg("printf 'hello\\n> ' >&2")
+    ///     .assert()
+    ///     .stderr_without_trailing("> ", "hello\n");
+    /// ```
+    pub fn stderr_without_trailing(self, trailer: &str, expected: &str) -> Self {
+        let (actual, removed) = strip_trailer(&self.output.stderr, trailer);
+        if actual != expected {
+            panic!(
+                "Unexpected stderr, with trailer `{}` {}\n{}\n{}",
+                trailer,
+                if removed { "removed" } else { "not found" },
+                diff_buffers(expected.as_bytes(), actual.as_bytes()),
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure the command wrote `expected` to `stderr`, allowing numbers to differ by `epsilon`.
+    ///
+    /// Both are split into whitespace-separated tokens.  Tokens that are both numbers are
+    /// compared within `epsilon`, other tokens must match exactly.  This tolerates floating-point
+    /// results differing in their last digits across platforms.  Failures point at the first
+    /// token that differs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stderr", "mean: 0.30000000000000004")
+    ///     .assert()
+    ///     .stderr_numeric_tolerant("mean: 0.3", 1e-9);
+    /// ```
+    pub fn stderr_numeric_tolerant(self, expected: &str, epsilon: f64) -> Self {
+        let actual = String::from_utf8_lossy(&self.output.stderr);
+        if let Err(err) = numeric_tolerant_eq(&actual, expected, epsilon) {
+            panic!("Unexpected stderr, {}\n{}", err, self);
+        }
+        self
+    }
+
+    /// Ensure the command's `stderr` is within `max` edits of `expected`.
+    ///
+    /// Edits are character insertions, deletions, and substitutions, i.e. the Levenshtein
+    /// distance.  This tolerates small, expected differences, like version numbers.  Failures
+    /// show the distance and the differences.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stderr", "tool 1.2.4")
+    ///     .assert()
+    ///     .stderr_within_distance("tool 1.2.3\n", 1);
+    /// ```
+    pub fn stderr_within_distance(self, expected: &str, max: usize) -> Self {
+        let actual = String::from_utf8_lossy(&self.output.stderr).into_owned();
+        let distance = edit_distance(
+            &expected.chars().collect::<Vec<_>>(),
+            &actual.chars().collect::<Vec<_>>(),
+        );
+        if max < distance {
+            panic!(
+                "Unexpected stderr, {} edits from expected, more than {}\n{}\n{}",
+                distance,
+                max,
+                diff_buffers(expected.as_bytes(), actual.as_bytes()),
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure the command wrote the lines of `expected` to `stderr`, in any order.
+    ///
+    /// The lines of both are sorted before comparing, so each line must appear as many times as
+    /// in `expected`, with no others.  Failures show the differences between the sorted lines.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stderr", "b\na\nb")
+    ///     .assert()
+    ///     .stderr_sorted_eq("a\nb\nb\n");
+    /// ```
+    pub fn stderr_sorted_eq(self, expected: &str) -> Self {
+        let actual = sorted_lines(&String::from_utf8_lossy(&self.output.stderr));
+        let expected = sorted_lines(expected);
+        if actual != expected {
+            panic!(
+                "Unexpected stderr, sorted lines differ\n{}\n{}",
+                diff_buffers(expected.as_bytes(), actual.as_bytes()),
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure `needle` appears exactly `count` times in the command's `stderr`.
+    ///
+    /// Occurrences are counted without overlapping.  Failures show the actual count.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stderr", "warning: a\nwarning: b\nwarning: c")
+    ///     .assert()
+    ///     .stderr_contains_count("warning:", 3);
+    /// ```
+    pub fn stderr_contains_count(self, needle: &str, count: usize) -> Self {
+        self.stderr_contains_count_matching(needle, predicates::ord::eq(count))
+    }
+
+    /// Ensure the number of times `needle` appears in the command's `stderr` matches `pred`.
+    ///
+    /// Occurrences are counted without overlapping.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// extern crate assert_cmd;
+    /// extern crate predicates;
+    ///
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use predicates::prelude::*;
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stderr", "warning: a\nwarning: b\nwarning: c")
+    ///     .assert()
+    ///     .stderr_contains_count_matching("warning:", predicate::ge(2));
+    /// ```
+    pub fn stderr_contains_count_matching<P>(self, needle: &str, pred: P) -> Self
+    where
+        P: predicates_core::Predicate<usize>,
+    {
+        let count = String::from_utf8_lossy(&self.output.stderr)
+            .matches(needle)
+            .count();
+        if let Some(case) = pred.find_case(false, &count) {
+            let message = format!(
+                "Unexpected stderr, `{}` appears {} times, failed {}",
+                needle,
+                count,
+                case.tree()
+            );
+            panic!("{}\n{}", self.explain(message, &pred), self);
+        }
+        self
+    }
+
+    /// Ensure no line of the command's `stderr` has a level above `level`.
+    ///
+    /// Levels are ordered `TRACE < DEBUG < INFO < WARN < ERROR`, compared case-insensitively.
+    /// A line's level is its leading token, ignoring surrounding punctuation like `[WARN]` or
+    /// `error:`, with `WARNING` accepted for `WARN`.  Lines without a level are ignored.
+    ///
+    /// # Panics
+    ///
+    /// If `level` is not one of the known levels.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stderr", "INFO starting\n[WARN] slow\ndone")
+    ///     .assert()
+    ///     .stderr_max_level("warn");
+    /// ```
+    pub fn stderr_max_level(self, level: &str) -> Self {
+        let max = log_level(level).unwrap_or_else(|| {
+            panic!(
+                "Unknown level `{}`, expected one of {:?}",
+                level, LOG_LEVELS
+            )
+        });
+        let offending: Vec<_> = String::from_utf8_lossy(&self.output.stderr)
+            .lines()
+            .filter(|line| {
+                matches!(
+                    line.split_whitespace().next().and_then(log_level),
+                    Some(actual) if actual > max
+                )
+            })
+            .map(|line| line.to_owned())
+            .collect();
+        if !offending.is_empty() {
+            panic!(
+                "Unexpected stderr, {} lines above {}\n{}\n{}",
+                offending.len(),
+                LOG_LEVELS[max],
+                offending.join("\n"),
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure the command wrote exactly one of `candidates` to `stderr`.
+    ///
+    /// Unlike a substring check, the whole of `stderr` must equal a candidate.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stderr", "hi")
+    ///     .assert()
+    ///     .stderr_one_of(&["hello\n", "hi\n"]);
+    /// ```
+    pub fn stderr_one_of(self, candidates: &[&str]) -> Self {
+        if let Err(err) = one_of(&self.output.stderr, candidates) {
+            panic!("Unexpected stderr, {}\n{}", err, self);
+        }
+        self
+    }
+
+    /// Ensure the command wrote the expected data to `stderr`, if it failed.
+    ///
+    /// A successful command passes regardless of `stderr`.  See [`Assert::stderr`] for the
+    /// accepted predicates.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("exit", "1")
+    ///     .env("stderr", "error")
+    ///     .assert()
+    ///     .on_success_stdout("hello\n")
+    ///     .on_failure_stderr("error\n");
+    /// ```
+    ///
+    /// [`Assert::stderr`]: #method.stderr
+    pub fn on_failure_stderr<I, P>(self, pred: I) -> Self
+    where
+        I: IntoOutputPredicate<P>,
+        P: predicates_core::Predicate<[u8]>,
+    {
+        if self.output.status.success() {
+            self
+        } else {
+            self.stderr_impl(&pred.into_output())
+        }
+    }
+
+    /// Ensure the command did not write exactly `value` to `stderr`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .env("stderr", "world")
+    ///     .assert()
+    ///     .stderr_is_not("error\n");
+    /// ```
+    pub fn stderr_is_not<V>(self, value: V) -> Self
+    where
+        V: Into<Vec<u8>>,
+    {
+        if self.output.stderr == value.into() {
+            panic!(
+                "Unexpected stderr, unexpectedly matched excluded value\n{}",
+                self
+            );
+        }
+        self
+    }
+
+    /// Ensure the command wrote the same data to `stderr` as can be read from `reader`.
+    ///
+    /// `reader` is compared incrementally, without buffering all of it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::fs;
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .env("stderr", "world")
+    ///     .assert()
+    ///     .stderr_eq_reader(fs::File::open("tests/fixtures/world.txt").unwrap());
+    /// ```
+    pub fn stderr_eq_reader<R>(self, reader: R) -> Self
+    where
+        R: io::Read,
+    {
+        let offset = first_difference(&self.output.stderr, reader);
+        match offset {
+            Ok(None) => {}
+            Ok(Some(offset)) => panic!(
+                "Unexpected stderr, differs from reader at offset {}\n{}",
+                offset, self
+            ),
+            Err(err) => panic!("Failed to read expected stderr: {}\n{}", err, self),
+        }
+        self
+    }
+
+    /// Ensure the command wrote the expected data to `fd`, as captured by
+    /// [`CommandRunExt::assert_with_fds`].
+    ///
+    /// This uses [`IntoOutputPredicate`] to provide short-hands for common cases.
+    ///
+    /// # Panics
+    ///
+    /// If `fd` wasn't captured.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("sh")
+    ///     .arg("-c")
+    ///     .arg("echo ready >&3")
+    ///     .assert_with_fds(&[3])
+    ///     .fd(3, "ready\n");
+    /// ```
+    ///
+    /// [`CommandRunExt::assert_with_fds`]: ../run/trait.CommandRunExt.html#tymethod.assert_with_fds
+    /// [`IntoOutputPredicate`]: trait.IntoOutputPredicate.html
+    #[cfg(unix)]
+    pub fn fd<I, P>(self, fd: i32, pred: I) -> Self
+    where
+        I: IntoOutputPredicate<P>,
+        P: predicates_core::Predicate<[u8]>,
+    {
+        self.fd_impl(fd, &pred.into_output())
+    }
+
+    #[cfg(unix)]
+    fn fd_impl(self, fd: i32, pred: &dyn predicates_core::Predicate<[u8]>) -> Self {
+        if let Some(case) = pred.find_case(false, self.get_extra_fd(fd)) {
+            let message = format!("Unexpected fd {}, failed {}", fd, case.tree());
+            panic!("{}\n{}", self.explain(message, pred), self);
+        }
+        self
+    }
+
+    /// Ensure the command left no processes running in its process group, like forgotten
+    /// children, as run by [`CommandRunExt::assert_with_process_group`].
+    ///
+    /// This is best-effort: children that exit on their own shortly after are reported.  Any
+    /// that are found are killed.
+    ///
+    /// # Panics
+    ///
+    /// If the process group wasn't tracked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .assert_with_process_group()
+    ///     .success()
+    ///     .no_orphans();
+    /// ```
+    ///
+    /// [`CommandRunExt::assert_with_process_group`]: ../run/trait.CommandRunExt.html#tymethod.assert_with_process_group
+    #[cfg(unix)]
+    pub fn no_orphans(self) -> Self {
+        use libc;
+
+        let pgid = self
+            .process_group
+            .expect("Process group not tracked, see `CommandRunExt::assert_with_process_group`");
+        if unsafe { libc::kill(-pgid, 0) } == 0 {
+            unsafe { libc::kill(-pgid, libc::SIGKILL) };
+            panic!(
+                "Unexpected processes left running in process group {}, killed\n{}",
+                pgid, self
+            );
+        }
+        self
+    }
+
+    /// Ensure the file the command wrote at `path` has the expected content.
+    ///
+    /// The file is read when asserting.  Like [`Assert::stdout`], this uses
+    /// [`IntoOutputPredicate`] to provide short-hands for common cases.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("sh")
+    ///     .arg("-c")
+    ///     .arg("echo hello > out.txt")
+    ///     .assert()
+    ///     .success()
+    ///     .output_file("out.txt", "hello\n");
+    /// ```
+    ///
+    /// [`Assert::stdout`]: #method.stdout
+    /// [`IntoOutputPredicate`]: trait.IntoOutputPredicate.html
+    pub fn output_file<F, I, P>(self, path: F, pred: I) -> Self
+    where
+        F: AsRef<path::Path>,
+        I: IntoOutputPredicate<P>,
+        P: predicates_core::Predicate<[u8]>,
+    {
+        self.output_file_impl(path.as_ref(), &pred.into_output())
+    }
+
+    fn output_file_impl(
+        self,
+        path: &path::Path,
+        pred: &dyn predicates_core::Predicate<[u8]>,
+    ) -> Self {
+        let actual = match fs::read(path) {
+            Ok(actual) => actual,
+            Err(err) => panic!(
+                "Unexpected output file, failed to read `{}`: {}\n{}",
+                path.display(),
+                err,
+                self
+            ),
+        };
+        if let Some(case) = pred.find_case(false, &actual) {
+            let message = format!(
+                "Unexpected output file `{}`, failed {}",
+                path.display(),
+                case.tree()
+            );
+            panic!("{}\n{}", self.explain(message, pred), self);
+        }
+        self
+    }
+
+    /// Ensure the command wrote nothing to either `stdout` or `stderr`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .assert()
+    ///     .silent();
+    /// ```
+    pub fn silent(self) -> Self {
+        if let Err(err) = self.check_silent() {
+            panic!("{}\n{}", err, self);
+        }
+        self
+    }
+
+    fn check_silent(&self) -> Result<(), String> {
+        let noisy: Vec<_> = vec![
+            ("stdout", &self.output.stdout),
+            ("stderr", &self.output.stderr),
+        ]
+        .into_iter()
+        .filter(|&(_, buffer)| !buffer.is_empty())
+        .map(|(stream, buffer)| format!("{} ({} bytes)", stream, buffer.len()))
+        .collect();
+        if !noisy.is_empty() {
+            return Err(format!("Unexpected output on {}", noisy.join(" and ")));
+        }
+        Ok(())
+    }
+
+    /// Ensure either `stdout` or `stderr` contains `needle`.
+    ///
+    /// Each stream is searched separately, so a match can't span the two.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stderr", "warning: deprecated")
+    ///     .assert()
+    ///     .output_contains("deprecated");
+    /// ```
+    pub fn output_contains<S>(self, needle: S) -> Self
+    where
+        S: Into<String>,
+    {
+        if let Err(err) = self.check_output_contains(&needle.into()) {
+            panic!("{}\n{}", err, self);
+        }
+        self
+    }
+
+    fn check_output_contains(&self, needle: &str) -> Result<(), String> {
+        let found = [&self.output.stdout, &self.output.stderr]
+            .iter()
+            .any(|buffer| find_bytes(buffer, needle.as_bytes()).is_some());
+        if !found {
+            return Err(format!(
+                "Unexpected output, `{}` in neither stdout nor stderr",
+                needle
+            ));
+        }
+        Ok(())
+    }
+
+    /// Ensure the command wrote fewer than `bytes` in total to `stdout` and `stderr`.
+    ///
+    /// Failures report the size of each stream and the total.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .env("stderr", "world")
+    ///     .assert()
+    ///     .total_output_below(1024);
+    /// ```
+    pub fn total_output_below(self, bytes: usize) -> Self {
+        if let Err(err) = self.check_total_output_below(bytes) {
+            panic!("{}\n{}", err, self);
+        }
+        self
+    }
+
+    fn check_total_output_below(&self, bytes: usize) -> Result<(), String> {
+        let stdout = self.output.stdout.len();
+        let stderr = self.output.stderr.len();
+        let total = stdout + stderr;
+        if total >= bytes {
+            return Err(format!(
+                "Unexpected output size, {} bytes in total (stdout {} bytes, stderr {} bytes), \
+                 expected below {} bytes",
+                total, stdout, stderr, bytes
+            ));
+        }
+        Ok(())
+    }
+
+    /// Ensure none of `secrets` appear in either `stdout` or `stderr`.
+    ///
+    /// Failures report where each secret leaked, redacting the secrets throughout the message,
+    /// including the output and context.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "token accepted")
+    ///     .assert()
+    ///     .assert_no_leak(&["hunter2-s3cr3t"]);
+    /// ```
+    pub fn assert_no_leak(self, secrets: &[&str]) -> Self {
+        let leaks: Vec<_> = secrets
+            .iter()
+            .filter(|secret| !secret.is_empty())
+            .filter_map(|secret| {
+                let streams: Vec<_> = vec![
+                    ("stdout", &self.output.stdout),
+                    ("stderr", &self.output.stderr),
+                ]
+                .into_iter()
+                .filter_map(|(stream, buffer)| {
+                    find_bytes(buffer, secret.as_bytes())
+                        .map(|offset| format!("{} at offset {}", stream, offset))
+                })
+                .collect();
+                if streams.is_empty() {
+                    None
+                } else {
+                    Some(format!(
+                        "Leaked `{}` to {}",
+                        redact(secret),
+                        streams.join(", ")
+                    ))
+                }
+            })
+            .collect();
+        if !leaks.is_empty() {
+            panic!("{}\n{}", leaks.join("\n"), self.redacted(secrets));
+        }
+        self
+    }
+
+    /// Replace `secrets` in the output and context, before they are rendered, elided, or escaped.
+    fn redacted(mut self, secrets: &[&str]) -> Self {
+        {
+            let buffers = vec![&mut self.output.stdout, &mut self.output.stderr]
+                .into_iter()
+                .chain(self.combined.as_mut())
+                .chain(self.extra_fds.iter_mut().map(|(_, buffer)| buffer));
+            for buffer in buffers {
+                *buffer = redact_bytes(buffer, secrets);
+            }
+        }
+        self.context = self
+            .context
+            .into_iter()
+            .map(|(name, context)| {
+                let text = redact_bytes(context.to_string().as_bytes(), secrets);
+                let text = String::from_utf8_lossy(&text).into_owned();
+                (name, Box::new(text) as Box<dyn fmt::Display>)
+            })
+            .collect();
+        self
+    }
+}
+
+/// Parse `buffer` as CSV, returning the header and the number of rows after it.
+///
+/// Errors include the line of the problem.
+#[cfg(feature = "csv")]
+fn read_csv(buffer: &[u8]) -> Result<(Vec<String>, usize), csv::Error> {
+    let mut reader = csv::Reader::from_reader(buffer);
+    let headers = reader.headers()?.iter().map(|s| s.to_owned()).collect();
+    let mut rows = 0;
+    for record in reader.records() {
+        record?;
+        rows += 1;
+    }
+    Ok((headers, rows))
+}
+
+/// Replace each of `secrets` in `buffer` with its redaction.
+///
+/// All are replaced in one pass, preferring the longest secret at each offset, so a redaction
+/// can't itself be rewritten by a later secret.
+fn redact_bytes(buffer: &[u8], secrets: &[&str]) -> Vec<u8> {
+    let mut secrets: Vec<_> = secrets.iter().filter(|secret| !secret.is_empty()).collect();
+    secrets.sort_by_key(|secret| cmp::Reverse(secret.len()));
+    let mut redacted = Vec::with_capacity(buffer.len());
+    let mut offset = 0;
+    while offset < buffer.len() {
+        let rest = &buffer[offset..];
+        match secrets
+            .iter()
+            .find(|secret| rest.starts_with(secret.as_bytes()))
+        {
+            Some(secret) => {
+                redacted.extend_from_slice(redact(secret).as_bytes());
+                offset += secret.len();
+            }
+            None => {
+                redacted.push(buffer[offset]);
+                offset += 1;
+            }
+        }
+    }
+    redacted
+}
+
+/// Hide all but a hint of `secret`.
+fn redact(secret: &str) -> String {
+    let chars = secret.chars().count();
+    if chars < 8 {
+        format!("<redacted, {} chars>", chars)
+    } else {
+        let hint: String = secret.chars().take(2).collect();
+        format!("{}<redacted, {} chars>", hint, chars)
+    }
+}
+
+/// Describe a predicate, its parameters, and its children, indented by `depth`.
+fn write_parameters<'a>(
+    out: &mut String,
+    name: &str,
+    parameters: Box<dyn Iterator<Item = predicates_core::reflection::Parameter<'a>> + 'a>,
+    children: Box<dyn Iterator<Item = predicates_core::reflection::Child<'a>> + 'a>,
+    depth: usize,
+) {
+    let indent = "  ".repeat(depth);
+    out.push_str(&format!("{}{}\n", indent, name));
+    for parameter in parameters {
+        out.push_str(&format!("{}  {}\n", indent, parameter));
+    }
+    for child in children {
+        let pred = child.value();
+        let name = format!("{}: {}", child.name(), pred);
+        write_parameters(out, &name, pred.parameters(), pred.children(), depth + 1);
+    }
+}
+
+/// Ensure `buffer` equals one of `candidates`.
+fn one_of(buffer: &[u8], candidates: &[&str]) -> Result<(), String> {
+    if candidates
+        .iter()
+        .any(|candidate| candidate.as_bytes() == buffer)
+    {
+        return Ok(());
+    }
+    let listing: Vec<_> = candidates
+        .iter()
+        .map(|candidate| format!("- ```{}```", candidate))
+        .collect();
+    Err(format!("matched none of:\n{}", listing.join("\n")))
+}
+
+/// Decode `buffer`, replacing matches of each regex pattern in turn.
+#[cfg(feature = "regex")]
+fn normalize(buffer: &[u8], replacements: &[(&str, &str)]) -> String {
+    let mut text = String::from_utf8_lossy(buffer).into_owned();
+    for &(pattern, replacement) in replacements {
+        let regex = regex::Regex::new(pattern)
+            .unwrap_or_else(|err| panic!("Invalid pattern `{}`: {}", pattern, err));
+        text = regex.replace_all(&text, replacement).into_owned();
+    }
+    text
+}
+
+/// Decode UTF-16LE, skipping a byte order mark, or return the offset of the invalid data.
+#[cfg(any(windows, test))]
+fn decode_utf16le(buffer: &[u8]) -> Result<String, usize> {
+    let skip = if buffer.starts_with(b"\xff\xfe") {
+        2
+    } else {
+        0
+    };
+    let pairs = buffer[skip..].chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return Err(buffer.len() - 1);
+    }
+    let units: Vec<_> = pairs
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    let mut decoded = String::with_capacity(units.len());
+    let mut offset = skip;
+    for c in char::decode_utf16(units.iter().cloned()) {
+        match c {
+            Ok(c) => {
+                decoded.push(c);
+                offset += 2 * c.len_utf16();
+            }
+            Err(_) => return Err(offset),
+        }
+    }
+    Ok(decoded)
+}
+
+/// Log levels for [`Assert::stderr_max_level`], from least to most severe.
+const LOG_LEVELS: &[&str] = &["TRACE", "DEBUG", "INFO", "WARN", "ERROR"];
+
+/// The severity of the log level named by `token`, as an index into [`LOG_LEVELS`].
+fn log_level(token: &str) -> Option<usize> {
+    let name = token
+        .trim_matches(|c: char| !c.is_ascii_alphabetic())
+        .to_ascii_uppercase();
+    let name = if name == "WARNING" { "WARN" } else { &name };
+    LOG_LEVELS.iter().position(|level| *level == name)
+}
+
+/// Whether `line` is a header for `section`, e.g. `USAGE:` or `Usage: prog [OPTIONS]`.
+fn is_section_header(line: &str, section: &str) -> bool {
+    let name = line.split(':').next().unwrap_or_default();
+    name.trim().eq_ignore_ascii_case(section.trim())
+}
+
+/// The text a terminal finally shows for `text`, with each `\r` returning to overwrite the line.
+fn final_display(text: &str) -> String {
+    let lines: Vec<_> = text
+        .split('\n')
+        .map(|line| {
+            line.split('\r')
+                .rfind(|segment| !segment.is_empty())
+                .unwrap_or_default()
+        })
+        .collect();
+    lines.join("\n")
+}
+
+/// Decode `buffer`, removing `trailer` from its end, if present, and whether it was.
+fn strip_trailer(buffer: &[u8], trailer: &str) -> (String, bool) {
+    let text = String::from_utf8_lossy(buffer);
+    match text.strip_suffix(trailer) {
+        Some(stripped) if !trailer.is_empty() => (stripped.to_owned(), true),
+        _ => (text.into_owned(), false),
+    }
+}
+
+/// Compare `actual` to `expected` token by token, allowing numbers to differ by `epsilon`.
+fn numeric_tolerant_eq(actual: &str, expected: &str, epsilon: f64) -> Result<(), String> {
+    let number = |token: &str| token.parse::<f64>().ok().filter(|n| n.is_finite());
+    let mut actual_tokens = actual.split_whitespace();
+    let mut expected_tokens = expected.split_whitespace();
+    for index in 0.. {
+        match (actual_tokens.next(), expected_tokens.next()) {
+            (None, None) => break,
+            (Some(a), Some(e)) => {
+                let matched = match (number(a), number(e)) {
+                    (Some(a), Some(e)) => (a - e).abs() <= epsilon,
+                    _ => a == e,
+                };
+                if !matched {
+                    return Err(format!(
+                        "token {} `{}` differs from expected `{}` (epsilon {})",
+                        index, a, e, epsilon
+                    ));
+                }
+            }
+            (Some(a), None) => {
+                return Err(format!("token {} `{}` is unexpected", index, a));
+            }
+            (None, Some(e)) => {
+                return Err(format!("token {} is missing, expected `{}`", index, e));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Byte-order marks, by encoding.
+const BOMS: &[(&str, &[u8])] = &[
+    ("UTF-8", b"\xef\xbb\xbf"),
+    ("UTF-16LE", b"\xff\xfe"),
+    ("UTF-16BE", b"\xfe\xff"),
+];
+
+/// The encoding and byte-order mark `buffer` starts with, if any.
+fn bom(buffer: &[u8]) -> Option<(&'static str, &'static [u8])> {
+    BOMS.iter()
+        .find(|&&(_, mark)| buffer.starts_with(mark))
+        .cloned()
+}
+
+/// The first few bytes of `buffer`, enough to show a byte-order mark.
+fn leading_bytes(buffer: &[u8]) -> &[u8] {
+    &buffer[..buffer.len().min(4)]
+}
+
+/// Render `bytes` as space-separated hex.
+fn hex(bytes: &[u8]) -> String {
+    let bytes: Vec<_> = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    bytes.join(" ")
+}
+
+/// The lines of `text`, sorted and each ending in a newline.
+fn sorted_lines(text: &str) -> String {
+    let mut lines: Vec<_> = text.lines().collect();
+    lines.sort();
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+/// The first `chars` characters of `text`.
+fn leading(text: &str, chars: usize) -> &str {
+    match text.char_indices().nth(chars) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+/// The last `chars` characters of `text`.
+fn trailing(text: &str, chars: usize) -> &str {
+    let skip = text.chars().count().saturating_sub(chars);
+    match text.char_indices().nth(skip) {
+        Some((start, _)) => &text[start..],
+        None => "",
+    }
+}
+
+/// Control characters accepted by [`Assert::stdout_is_ascii`] and [`Assert::stderr_is_ascii`].
+const DEFAULT_CONTROLS: &[u8] = b"\n\t";
+
+/// Offset of the first byte in `buffer` that is neither printable ASCII nor in `controls`.
+fn find_non_ascii(buffer: &[u8], controls: &[u8]) -> Option<usize> {
+    buffer
+        .iter()
+        .position(|b| !(b' '..=b'~').contains(b) && !controls.contains(b))
+}
+
+/// Remove the common indentation from `text`, like the `indoc` crate.
+///
+/// See [`Assert::stdout_indoc`] for the rules.
+///
+/// [`Assert::stdout_indoc`]: struct.Assert.html#method.stdout_indoc
+pub(crate) fn dedent(text: &str) -> String {
+    let text = text
+        .strip_prefix('\n')
+        .or_else(|| text.strip_prefix("\r\n"))
+        .unwrap_or(text);
+    let indent = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start_matches(&[' ', '\t'][..]).len())
+        .min()
+        .unwrap_or(0);
+    let mut dedented = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            dedented.push_str(&line[line.trim_end_matches(&['\r', '\n'][..]).len()..]);
+        } else {
+            dedented.push_str(&line[indent..]);
+        }
+    }
+    dedented
+}
+
+/// Describe `cmd`, including the program and argument count in case the full command is
+/// unclear, like when a shell script is being run.
+///
+/// When `ASSERT_CMD_SHOW_ENV` is set, the environment variables set or removed on `cmd` are
+/// included.
+pub(crate) fn command_context(cmd: &process::Command) -> Vec<(&'static str, String)> {
+    let program = path::Path::new(cmd.get_program()).display().to_string();
+    let argc = cmd.get_args().len();
+    let mut context = vec![
+        ("command", format!("{:?}", cmd)),
+        ("program", program),
+        ("argc", argc.to_string()),
+    ];
+    let path = cmd
+        .get_envs()
+        .find(|&(key, _)| key == "PATH")
+        .and_then(|(_, value)| value);
+    if let Some(path) = path {
+        context.push(("PATH", path.to_string_lossy().into_owned()));
+    }
+    if let Some(color) = color_choice(cmd) {
+        context.push(("color", color.to_owned()));
+    }
+    if let Some(config) = config_file(cmd) {
+        context.push(("config", config));
+    }
+    if show_env() {
+        let env: Vec<_> = cmd
+            .get_envs()
+            .map(|(key, value)| match value {
+                Some(value) => format!("{}={:?}", key.to_string_lossy(), value),
+                None => format!("{}=<removed>", key.to_string_lossy()),
+            })
+            .collect();
+        context.push(("env", env.join(" ")));
+    }
+    context
+}
+
+/// The color choice made by [`CommandRunExt::no_color`] or [`CommandRunExt::force_color`].
+fn color_choice(cmd: &process::Command) -> Option<&'static str> {
+    let mut no_color = None;
+    let mut force_color = None;
+    for (key, value) in cmd.get_envs() {
+        if key == "NO_COLOR" {
+            no_color = Some(value.is_some());
+        } else if key == "CLICOLOR_FORCE" {
+            force_color = Some(value.is_some());
+        }
+    }
+    match (no_color, force_color) {
+        (Some(true), Some(false)) => Some("never"),
+        (Some(false), Some(true)) => Some("always"),
+        _ => None,
+    }
+}
+
+/// The config file passed by [`CommandRunExt::with_config_file`].
+fn config_file(cmd: &process::Command) -> Option<String> {
+    cmd.get_args()
+        .map(path::Path::new)
+        .find(|arg| match arg.file_name().and_then(|name| name.to_str()) {
+            Some(name) => name.starts_with(run::CONFIG_FILE_PREFIX),
+            None => false,
+        })
+        .map(|config| config.display().to_string())
+}
+
+fn show_env() -> bool {
+    match env::var_os("ASSERT_CMD_SHOW_ENV") {
+        Some(value) => !value.is_empty() && value != "0",
+        None => false,
+    }
+}
+
+/// The number of insertions, deletions, and substitutions to turn `a` into `b`.
+fn edit_distance<T>(a: &[T], b: &[T]) -> usize
+where
+    T: PartialEq,
+{
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, a_item) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, b_item) in b.iter().enumerate() {
+            let substitution = previous[j] + if a_item == b_item { 0 } else { 1 };
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        ::std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// Find the offset where `actual` and the content of `reader` first diverge.
+pub(crate) fn first_difference<R>(actual: &[u8], mut reader: R) -> io::Result<Option<usize>>
+where
+    R: io::Read,
+{
+    let mut buffer = [0; 8 * 1024];
+    let mut offset = 0;
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        let expected = &buffer[..read];
+        let remaining = &actual[offset..];
+        if let Some(index) = expected
+            .iter()
+            .zip(remaining.iter())
+            .position(|(e, a)| e != a)
+        {
+            return Ok(Some(offset + index));
+        }
+        if remaining.len() < expected.len() {
+            return Ok(Some(actual.len()));
+        }
+        offset += read;
+    }
+    if offset < actual.len() {
+        Ok(Some(offset))
+    } else {
+        Ok(None)
+    }
+}
+
+impl fmt::Display for Assert {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for &(ref name, ref context) in &self.context {
+            writeln!(f, "{}=`{}`", name, context)?;
+        }
+        output_fmt(&self.output, self.line_width, f)?;
+        for &(fd, ref buffer) in &self.extra_fds {
+            write!(f, "fd{}=```", fd)?;
+            write_buffer(buffer, self.line_width, f)?;
+            writeln!(f, "```")?;
+        }
+        if let Some(ref combined) = self.combined {
+            write!(f, "combined=```")?;
+            write_buffer(combined, self.line_width, f)?;
+            writeln!(f, "```")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Assert {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Assert")
+            .field("output", &self.output)
+            .finish()
+    }
+}
+
+/// Assert the state of an [`Output`], collecting all failures.
+///
+/// Create a `SoftAssert` through [`Assert::soft`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use assert_cmd::prelude::*;
+///
+/// use std::process::Command;
+///
+/// Command::cargo_bin("bin_fixture")
+///     .unwrap()
+///     .env("stdout", "hello")
+///     .env("stderr", "world")
+///     .assert()
+///     .soft()
+///     .code(0)
+///     .stdout("hello\n")
+///     .stderr("world\n")
+///     .report();
+/// ```
+///
+/// [`Output`]: https://doc.rust-lang.org/std/process/struct.Output.html
+/// [`Assert::soft`]: struct.Assert.html#method.soft
+#[derive(Debug)]
+pub struct SoftAssert {
+    assert: Assert,
+    failures: Vec<String>,
+}
+
+impl SoftAssert {
+    /// Check the command succeeded.
+    ///
+    /// See [`Assert::success`].
+    ///
+    /// [`Assert::success`]: struct.Assert.html#method.success
+    pub fn success(self) -> Self {
+        let result = self.assert.check_success();
+        self.push(result)
+    }
+
+    /// Check the command failed.
+    ///
+    /// See [`Assert::failure`].
+    ///
+    /// [`Assert::failure`]: struct.Assert.html#method.failure
+    pub fn failure(self) -> Self {
+        let result = self.assert.check_failure();
+        self.push(result)
+    }
+
+    /// Check the command failed and explained why on `stderr`.
     ///
-    /// # Examples
+    /// See [`Assert::failure_with_stderr`].
     ///
-    /// Accepting a bytes predicate:
-    /// ```rust,no_run
-    /// extern crate assert_cmd;
-    /// extern crate predicates;
+    /// [`Assert::failure_with_stderr`]: struct.Assert.html#method.failure_with_stderr
+    pub fn failure_with_stderr(self) -> Self {
+        let result = self.assert.check_failure_with_stderr();
+        self.push(result)
+    }
+
+    /// Check the command returned a nonzero code.
     ///
-    /// use assert_cmd::prelude::*;
+    /// See [`Assert::nonzero_code`].
     ///
-    /// use std::process::Command;
-    /// use predicates::prelude::*;
+    /// [`Assert::nonzero_code`]: struct.Assert.html#method.nonzero_code
+    pub fn nonzero_code(self) -> Self {
+        let result = self.assert.check_nonzero_code();
+        self.push(result)
+    }
+
+    /// Check the command aborted before returning a code.
     ///
-    /// Command::cargo_bin("bin_fixture")
-    ///     .unwrap()
-    ///     .env("stdout", "hello")
-    ///     .env("stderr", "world")
-    ///     .assert()
-    ///     .stderr(predicate::eq(b"world\n" as &[u8]));
-    /// ```
+    /// See [`Assert::interrupted`].
     ///
-    /// Accepting a `str` predicate:
-    /// ```rust,no_run
-    /// extern crate assert_cmd;
-    /// extern crate predicates;
+    /// [`Assert::interrupted`]: struct.Assert.html#method.interrupted
+    pub fn interrupted(self) -> Self {
+        let result = self.assert.check_interrupted();
+        self.push(result)
+    }
+
+    /// Check the command exited normally, returning a code.
     ///
-    /// use assert_cmd::prelude::*;
+    /// See [`Assert::exited_normally`].
     ///
-    /// use std::process::Command;
-    /// use predicates::prelude::*;
+    /// [`Assert::exited_normally`]: struct.Assert.html#method.exited_normally
+    #[cfg(unix)]
+    pub fn exited_normally(self) -> Self {
+        let result = self.assert.check_exited_normally();
+        self.push(result)
+    }
+
+    /// Check the command was killed by a signal, `signal` if given.
     ///
-    /// Command::cargo_bin("bin_fixture")
-    ///     .unwrap()
-    ///     .env("stdout", "hello")
-    ///     .env("stderr", "world")
-    ///     .assert()
-    ///     .stderr(predicate::str::similar("world\n"));
-    /// ```
+    /// See [`Assert::killed_by_signal`].
     ///
-    /// Accepting bytes:
-    /// ```rust,no_run
-    /// use assert_cmd::prelude::*;
+    /// [`Assert::killed_by_signal`]: struct.Assert.html#method.killed_by_signal
+    #[cfg(unix)]
+    pub fn killed_by_signal(self, signal: Option<i32>) -> Self {
+        let result = self.assert.check_killed_by_signal(signal);
+        self.push(result)
+    }
+
+    /// Check the command returned the expected code.
     ///
-    /// use std::process::Command;
+    /// See [`Assert::code`].
     ///
-    /// Command::cargo_bin("bin_fixture")
-    ///     .unwrap()
-    ///     .env("stdout", "hello")
-    ///     .env("stderr", "world")
-    ///     .assert()
-    ///     .stderr(b"world\n" as &[u8]);
-    /// ```
+    /// [`Assert::code`]: struct.Assert.html#method.code
+    pub fn code<I, P>(self, pred: I) -> Self
+    where
+        I: IntoCodePredicate<P>,
+        P: predicates_core::Predicate<i32>,
+    {
+        let result = self.assert.check_code(&pred.into_code());
+        self.push(result)
+    }
+
+    /// Check the command wrote the expected data to `stdout`.
     ///
-    /// Accepting a `str`:
-    /// ```rust,no_run
-    /// use assert_cmd::prelude::*;
+    /// See [`Assert::stdout`].
     ///
-    /// use std::process::Command;
+    /// [`Assert::stdout`]: struct.Assert.html#method.stdout
+    pub fn stdout<I, P>(self, pred: I) -> Self
+    where
+        I: IntoOutputPredicate<P>,
+        P: predicates_core::Predicate<[u8]>,
+    {
+        let result = self.assert.check_stdout(&pred.into_output());
+        self.push(result)
+    }
+
+    /// Check the command wrote the expected data to `stderr`.
     ///
-    /// Command::cargo_bin("bin_fixture")
-    ///     .unwrap()
-    ///     .env("stdout", "hello")
-    ///     .env("stderr", "world")
-    ///     .assert()
-    ///     .stderr("world\n");
-    /// ```
+    /// See [`Assert::stderr`].
     ///
-    /// [`predicates`]: https://docs.rs/predicates
-    /// [`IntoOutputPredicate`]: trait.IntoOutputPredicate.html
+    /// [`Assert::stderr`]: struct.Assert.html#method.stderr
     pub fn stderr<I, P>(self, pred: I) -> Self
     where
         I: IntoOutputPredicate<P>,
         P: predicates_core::Predicate<[u8]>,
     {
-        self.stderr_impl(&pred.into_output())
+        let result = self.assert.check_stderr(&pred.into_output());
+        self.push(result)
     }
 
-    fn stderr_impl(self, pred: &predicates_core::Predicate<[u8]>) -> Self {
-        {
-            let actual = &self.output.stderr;
-            if let Some(case) = pred.find_case(false, &actual) {
-                panic!("Unexpected stderr, failed {}\n\n{}", case.tree(), self);
-            }
+    /// Check either `stdout` or `stderr` contains `needle`.
+    ///
+    /// See [`Assert::output_contains`].
+    ///
+    /// [`Assert::output_contains`]: struct.Assert.html#method.output_contains
+    pub fn output_contains<S>(self, needle: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let result = self.assert.check_output_contains(&needle.into());
+        self.push(result)
+    }
+
+    /// Check the command wrote fewer than `bytes` in total to `stdout` and `stderr`.
+    ///
+    /// See [`Assert::total_output_below`].
+    ///
+    /// [`Assert::total_output_below`]: struct.Assert.html#method.total_output_below
+    pub fn total_output_below(self, bytes: usize) -> Self {
+        let result = self.assert.check_total_output_below(bytes);
+        self.push(result)
+    }
+
+    /// Check the command wrote nothing to either `stdout` or `stderr`.
+    ///
+    /// See [`Assert::silent`].
+    ///
+    /// [`Assert::silent`]: struct.Assert.html#method.silent
+    pub fn silent(self) -> Self {
+        let result = self.assert.check_silent();
+        self.push(result)
+    }
+
+    fn push(mut self, result: Result<(), String>) -> Self {
+        if let Err(failure) = result {
+            self.failures.push(failure);
         }
         self
     }
-}
 
-impl fmt::Display for Assert {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for &(ref name, ref context) in &self.context {
-            writeln!(f, "{}=`{}`", name, context)?;
+    /// Panic with every failure collected, if any.
+    pub fn report(self) -> Assert {
+        match self.try_report() {
+            Ok(assert) => assert,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Return every failure collected, if any.
+    pub fn try_report(self) -> Result<Assert, AssertError> {
+        if self.failures.is_empty() {
+            Ok(self.assert)
+        } else {
+            Err(AssertError {
+                failures: self.failures,
+                assert: self.assert.to_string(),
+            })
         }
-        output_fmt(&self.output, f)
     }
 }
 
-impl fmt::Debug for Assert {
+/// Failures collected by a [`SoftAssert`].
+///
+/// [`SoftAssert`]: struct.SoftAssert.html
+#[derive(Debug)]
+pub struct AssertError {
+    failures: Vec<String>,
+    assert: String,
+}
+
+impl AssertError {
+    /// The messages for each failed assertion.
+    pub fn failures(&self) -> &[String] {
+        &self.failures
+    }
+}
+
+impl Error for AssertError {
+    fn description(&self) -> &str {
+        "Assertions failed."
+    }
+}
+
+impl fmt::Display for AssertError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.debug_struct("Assert")
-            .field("output", &self.output)
-            .finish()
+        writeln!(f, "{} assertion(s) failed", self.failures.len())?;
+        for failure in &self.failures {
+            writeln!(f, "{}", failure)?;
+        }
+        write!(f, "{}", self.assert)
     }
 }
 
@@ -608,22 +4686,17 @@ impl IntoCodePredicate<EqCodePredicate> for i32 {
 /// [`IntoCodePredicate`]: trait.IntoCodePredicate.html
 /// [Predicate]: https://docs.rs/predicates-core/1.0.0/predicates_core/trait.Predicate.html
 #[derive(Debug)]
-pub struct InCodePredicate(predicates::iter::InPredicate<i32>);
+pub struct InCodePredicate(predicates::iter::InPredicate<i32>, Vec<i32>);
 
 impl InCodePredicate {
     pub(crate) fn new<I: IntoIterator<Item = i32>>(value: I) -> Self {
-        let pred = predicates::iter::in_iter(value);
-        InCodePredicate(pred)
+        let codes: Vec<_> = value.into_iter().collect();
+        let pred = predicates::iter::in_iter(codes.clone());
+        InCodePredicate(pred, codes)
     }
 }
 
 impl predicates_core::reflection::PredicateReflection for InCodePredicate {
-    fn parameters<'a>(
-        &'a self,
-    ) -> Box<Iterator<Item = predicates_core::reflection::Parameter<'a>> + 'a> {
-        self.0.parameters()
-    }
-
     /// Nested `Predicate`s of the current `Predicate`.
     fn children<'a>(&'a self) -> Box<Iterator<Item = predicates_core::reflection::Child<'a>> + 'a> {
         self.0.children()
@@ -640,13 +4713,19 @@ impl predicates_core::Predicate<i32> for InCodePredicate {
         expected: bool,
         variable: &i32,
     ) -> Option<predicates_core::reflection::Case<'a>> {
-        self.0.find_case(expected, variable)
+        // Report against ourself so the codes are listed rather than `predicates`' summary.
+        let actual = self.eval(variable);
+        if expected == actual {
+            Some(predicates_core::reflection::Case::new(Some(self), actual))
+        } else {
+            None
+        }
     }
 }
 
 impl fmt::Display for InCodePredicate {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        self.0.fmt(f)
+        write!(f, "var in {:?}", self.1)
     }
 }
 
@@ -666,6 +4745,25 @@ impl IntoCodePredicate<InCodePredicate> for &'static [i32] {
     }
 }
 
+impl IntoCodePredicate<InCodePredicate> for collections::BTreeSet<i32> {
+    type Predicate = InCodePredicate;
+
+    fn into_code(self) -> Self::Predicate {
+        Self::Predicate::new(self)
+    }
+}
+
+impl IntoCodePredicate<InCodePredicate> for collections::HashSet<i32> {
+    type Predicate = InCodePredicate;
+
+    fn into_code(self) -> Self::Predicate {
+        // Sorted, so failures list the codes consistently.
+        let mut codes: Vec<_> = self.into_iter().collect();
+        codes.sort();
+        Self::Predicate::new(codes)
+    }
+}
+
 /// Used by [`Assert::stdout`] and [`Assert::stderr`] to convert Self
 /// into the needed [`Predicate<[u8]>`].
 ///
@@ -853,7 +4951,10 @@ impl predicates_core::Predicate<[u8]> for StrContentOutputPredicate {
         expected: bool,
         variable: &[u8],
     ) -> Option<predicates_core::reflection::Case<'a>> {
-        self.0.find_case(expected, variable)
+        match str::from_utf8(variable) {
+            Err(err) if !expected => Some(invalid_utf8_case(self, &err)),
+            _ => self.0.find_case(expected, variable),
+        }
     }
 }
 
@@ -871,6 +4972,14 @@ impl IntoOutputPredicate<StrContentOutputPredicate> for String {
     }
 }
 
+impl IntoOutputPredicate<StrContentOutputPredicate> for &String {
+    type Predicate = StrContentOutputPredicate;
+
+    fn into_output(self) -> Self::Predicate {
+        Self::Predicate::from_string(self.clone())
+    }
+}
+
 impl IntoOutputPredicate<StrContentOutputPredicate> for &'static str {
     type Predicate = StrContentOutputPredicate;
 
@@ -879,6 +4988,19 @@ impl IntoOutputPredicate<StrContentOutputPredicate> for &'static str {
     }
 }
 
+/// Report output failing `pred` by not being UTF-8, rather than by its content.
+fn invalid_utf8_case<'a>(
+    pred: &'a dyn predicates_core::reflection::PredicateReflection,
+    err: &str::Utf8Error,
+) -> predicates_core::reflection::Case<'a> {
+    let error = format!(
+        "not valid UTF-8 (invalid byte at offset {})",
+        err.valid_up_to()
+    );
+    predicates_core::reflection::Case::new(Some(pred), false)
+        .add_product(predicates_core::reflection::Product::new("error", error))
+}
+
 // Keep `predicates` concrete Predicates out of our public API.
 /// [Predicate] used by [`IntoOutputPredicate`] for [`Predicate<str>`].
 ///
@@ -947,7 +5069,10 @@ where
         expected: bool,
         variable: &[u8],
     ) -> Option<predicates_core::reflection::Case<'a>> {
-        self.0.find_case(expected, variable)
+        match str::from_utf8(variable) {
+            Err(err) if !expected => Some(invalid_utf8_case(self, &err)),
+            _ => self.0.find_case(expected, variable),
+        }
     }
 }
 
@@ -971,6 +5096,117 @@ where
     }
 }
 
+/// [Predicate] evaluating `P` on UTF-8 output with a prefix stripped from each line.
+///
+/// On failure, both the raw and the stripped output are reported.
+///
+/// [Predicate]: https://docs.rs/predicates-core/1.0.0/predicates_core/trait.Predicate.html
+#[cfg(feature = "regex")]
+#[derive(Debug, Clone)]
+struct StripPrefixPredicate<P> {
+    prefix: regex::Regex,
+    pred: P,
+}
+
+#[cfg(feature = "regex")]
+impl<P> StripPrefixPredicate<P>
+where
+    P: predicates_core::Predicate<str>,
+{
+    fn new(prefix: &str, pred: P) -> Self {
+        let anchored = format!("^(?:{})", prefix);
+        let prefix = regex::Regex::new(&anchored)
+            .unwrap_or_else(|err| panic!("Invalid prefix `{}`: {}", prefix, err));
+        StripPrefixPredicate { prefix, pred }
+    }
+
+    fn strip(&self, raw: &str) -> String {
+        raw.split_inclusive('\n')
+            .map(|line| self.prefix.replace(line, ""))
+            .collect()
+    }
+}
+
+#[cfg(feature = "regex")]
+impl<P> predicates_core::reflection::PredicateReflection for StripPrefixPredicate<P>
+where
+    P: predicates_core::Predicate<str>,
+{
+    fn parameters<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = predicates_core::reflection::Parameter<'a>> + 'a> {
+        let params = vec![predicates_core::reflection::Parameter::new(
+            "prefix",
+            &self.prefix,
+        )];
+        Box::new(params.into_iter())
+    }
+
+    fn children<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = predicates_core::reflection::Child<'a>> + 'a> {
+        let children = vec![predicates_core::reflection::Child::new(
+            "predicate",
+            &self.pred,
+        )];
+        Box::new(children.into_iter())
+    }
+}
+
+#[cfg(feature = "regex")]
+impl<P> predicates_core::Predicate<[u8]> for StripPrefixPredicate<P>
+where
+    P: predicates_core::Predicate<str>,
+{
+    fn eval(&self, item: &[u8]) -> bool {
+        match str::from_utf8(item) {
+            Ok(raw) => self.pred.eval(&self.strip(raw)),
+            Err(_) => false,
+        }
+    }
+
+    fn find_case<'a>(
+        &'a self,
+        expected: bool,
+        variable: &[u8],
+    ) -> Option<predicates_core::reflection::Case<'a>> {
+        let raw = match str::from_utf8(variable) {
+            Ok(raw) => raw,
+            Err(err) => {
+                return if expected {
+                    None
+                } else {
+                    let case = predicates_core::reflection::Case::new(Some(self), false)
+                        .add_product(predicates_core::reflection::Product::new("error", err));
+                    Some(case)
+                };
+            }
+        };
+        let stripped = self.strip(raw);
+        let child = self.pred.find_case(expected, &stripped)?;
+        let case = predicates_core::reflection::Case::new(Some(self), child.result())
+            .add_product(predicates_core::reflection::Product::new(
+                "raw",
+                raw.to_owned(),
+            ))
+            .add_product(predicates_core::reflection::Product::new(
+                "stripped", stripped,
+            ))
+            .add_child(child);
+        Some(case)
+    }
+}
+
+#[cfg(feature = "regex")]
+impl<P> fmt::Display for StripPrefixPredicate<P>
+where
+    P: predicates_core::Predicate<str>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} after stripping prefix", self.pred)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1005,12 +5241,43 @@ mod test {
         assert!(pred.eval(&10));
     }
 
+    #[test]
+    fn into_code_from_sets() {
+        let pred = convert_code(
+            vec![3, 10]
+                .into_iter()
+                .collect::<collections::BTreeSet<_>>(),
+        );
+        assert!(pred.eval(&10));
+        let pred = convert_code(vec![10, 3].into_iter().collect::<collections::HashSet<_>>());
+        assert!(pred.eval(&10));
+        assert_eq!(pred.to_string(), "var in [3, 10]");
+    }
+
     #[test]
     fn into_code_from_array() {
         let pred = convert_code(&[3, 10] as &[i32]);
         assert!(pred.eval(&10));
     }
 
+    #[test]
+    fn dedent_common_indent() {
+        assert_eq!(dedent("\n    a\n      b\n\n    c\n    "), "a\n  b\n\nc\n");
+    }
+
+    #[test]
+    fn dedent_without_leading_newline() {
+        assert_eq!(dedent("  a\n    b"), "a\n  b");
+        assert_eq!(dedent("a\n  b\n"), "a\n  b\n");
+    }
+
+    #[test]
+    fn in_code_lists_codes() {
+        let pred = convert_code(&[3, 10] as &[i32]);
+        let case = pred.find_case(false, &7).unwrap();
+        assert_eq!(case.tree().to_string(), "var in [3, 10]\n");
+    }
+
     // Since IntoOutputPredicate exists solely for conversion, test it under that scenario to ensure
     // it works as expected.
     fn convert_output<I, P>(pred: I) -> P
@@ -1038,4 +5305,97 @@ mod test {
         let pred = convert_output("Hello");
         assert!(pred.eval(b"Hello" as &[u8]));
     }
+
+    #[test]
+    fn into_output_from_string_ref() {
+        let expected = "Hello".to_owned();
+        let pred = convert_output(&expected);
+        assert!(pred.eval(b"Hello" as &[u8]));
+    }
+
+    #[test]
+    fn edit_distance_lines() {
+        assert_eq!(edit_distance(&["a", "b", "c"], &["a", "b", "c"]), 0);
+        assert_eq!(edit_distance(&["a", "b", "c"], &["a", "c"]), 1);
+        assert_eq!(edit_distance(&["a", "b"], &["x", "y", "z"]), 3);
+        assert_eq!(edit_distance::<&str>(&[], &["a"]), 1);
+    }
+
+    #[test]
+    fn first_difference_equal() {
+        let offset = first_difference(b"Hello", &b"Hello"[..]).unwrap();
+        assert_eq!(offset, None);
+    }
+
+    #[test]
+    fn first_difference_content() {
+        let offset = first_difference(b"Hello", &b"Help"[..]).unwrap();
+        assert_eq!(offset, Some(3));
+    }
+
+    #[test]
+    fn first_difference_length() {
+        let offset = first_difference(b"Hello", &b"Hello world"[..]).unwrap();
+        assert_eq!(offset, Some(5));
+        let offset = first_difference(b"Hello world", &b"Hello"[..]).unwrap();
+        assert_eq!(offset, Some(5));
+    }
+
+    #[test]
+    fn leading_and_trailing_chars() {
+        assert_eq!(leading("h\u{e9}llo", 2), "h\u{e9}");
+        assert_eq!(leading("hi", 5), "hi");
+        assert_eq!(trailing("hell\u{f6}", 2), "l\u{f6}");
+        assert_eq!(trailing("hi", 5), "hi");
+        assert_eq!(trailing("hi", 0), "");
+    }
+
+    #[test]
+    fn numeric_tolerant_tokens() {
+        assert_eq!(numeric_tolerant_eq("x 1.0001 y", "x 1 y", 0.001), Ok(()));
+        assert_eq!(
+            numeric_tolerant_eq("x 1.1", "x 1", 0.001),
+            Err("token 1 `1.1` differs from expected `1` (epsilon 0.001)".to_owned())
+        );
+        assert_eq!(
+            numeric_tolerant_eq("x", "x 1", 0.001),
+            Err("token 1 is missing, expected `1`".to_owned())
+        );
+        assert!(numeric_tolerant_eq("inf", "NaN", 1.0).is_err());
+    }
+
+    #[test]
+    fn section_headers() {
+        assert!(is_section_header("USAGE:", "USAGE"));
+        assert!(is_section_header("Usage: prog [OPTIONS]", "usage"));
+        assert!(is_section_header("Options", "OPTIONS"));
+        assert!(!is_section_header("    -h, --help  Print usage", "USAGE"));
+    }
+
+    #[test]
+    fn final_display_overwrites() {
+        assert_eq!(final_display(" 50%\r100%\ndone\n"), "100%\ndone\n");
+        assert_eq!(final_display("50%\r100%\r\n"), "100%\n");
+        assert_eq!(final_display("a\r\nb"), "a\nb");
+    }
+
+    #[test]
+    fn log_level_tokens() {
+        assert_eq!(log_level("[WARN]"), Some(3));
+        assert_eq!(log_level("error:"), Some(4));
+        assert_eq!(log_level("Warning:"), Some(3));
+        assert_eq!(log_level("starting"), None);
+    }
+
+    #[test]
+    fn decode_utf16le_bom_and_surrogates() {
+        assert_eq!(decode_utf16le(b"\xff\xfeh\x00i\x00").unwrap(), "hi");
+        assert_eq!(decode_utf16le(b"=\xd8\x00\xde").unwrap(), "\u{1f600}");
+    }
+
+    #[test]
+    fn decode_utf16le_invalid_offset() {
+        assert_eq!(decode_utf16le(b"h\x00i"), Err(2));
+        assert_eq!(decode_utf16le(b"\xff\xfeh\x00\x00\xdc"), Err(4));
+    }
 }
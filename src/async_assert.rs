@@ -0,0 +1,138 @@
+//! [`Output`][Output] assertions for [`tokio::process::Command`][Command].
+//!
+//! [Output]: https://doc.rust-lang.org/std/process/struct.Output.html
+//! [Command]: https://docs.rs/tokio/1/tokio/process/struct.Command.html
+
+use std::fmt;
+use std::future;
+use std::io;
+use std::pin;
+use std::process;
+use std::task;
+
+use tokio;
+
+use assert::command_context;
+use assert::Assert;
+
+/// Assert the state of the [`Output`] of a [`tokio::process::Command`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// extern crate assert_cmd;
+/// extern crate tokio;
+///
+/// use assert_cmd::prelude::*;
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread()
+///     .enable_all()
+///     .build()
+///     .unwrap();
+/// let mut cmd = tokio::process::Command::new("echo");
+/// cmd.arg("hello");
+/// runtime.block_on(cmd.assert())
+///     .success()
+///     .stdout("hello\n");
+/// ```
+///
+/// [`Output`]: https://doc.rust-lang.org/std/process/struct.Output.html
+/// [`tokio::process::Command`]: https://docs.rs/tokio/1/tokio/process/struct.Command.html
+pub trait AsyncOutputAssertExt {
+    /// The [`Future`] resolving to an [`Assert`].
+    ///
+    /// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+    /// [`Assert`]: ../assert/struct.Assert.html
+    type Future: future::Future<Output = Assert>;
+
+    /// Run the command, wrapping its [`Output`] with an interface that provides assertions.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// extern crate assert_cmd;
+    /// extern crate tokio;
+    ///
+    /// use assert_cmd::prelude::*;
+    ///
+    /// let runtime = tokio::runtime::Builder::new_current_thread()
+    ///     .enable_all()
+    ///     .build()
+    ///     .unwrap();
+    /// let mut cmd = tokio::process::Command::new("echo");
+    /// cmd.arg("hello");
+    /// runtime.block_on(cmd.assert())
+    ///     .success()
+    ///     .stdout("hello\n");
+    /// ```
+    ///
+    /// [`Output`]: https://doc.rust-lang.org/std/process/struct.Output.html
+    fn assert(self) -> Self::Future;
+}
+
+impl<'c> AsyncOutputAssertExt for &'c mut tokio::process::Command {
+    type Future = AssertFuture<'c>;
+
+    fn assert(self) -> Self::Future {
+        let context = command_context(self.as_std());
+        AssertFuture {
+            cmd: Some(self),
+            output: None,
+            context: Some(context),
+        }
+    }
+}
+
+/// [`Future`] for running a [`tokio::process::Command`] and asserting on its [`Output`].
+///
+/// Create an `AssertFuture` through the [`AsyncOutputAssertExt`] trait.
+///
+/// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+/// [`tokio::process::Command`]: https://docs.rs/tokio/1/tokio/process/struct.Command.html
+/// [`Output`]: https://doc.rust-lang.org/std/process/struct.Output.html
+/// [`AsyncOutputAssertExt`]: trait.AsyncOutputAssertExt.html
+pub struct AssertFuture<'c> {
+    cmd: Option<&'c mut tokio::process::Command>,
+    output:
+        Option<pin::Pin<Box<dyn future::Future<Output = io::Result<process::Output>> + Send + 'c>>>,
+    context: Option<Vec<(&'static str, String)>>,
+}
+
+impl<'c> future::Future for AssertFuture<'c> {
+    type Output = Assert;
+
+    fn poll(mut self: pin::Pin<&mut Self>, cx: &mut task::Context) -> task::Poll<Assert> {
+        // `Command::output` spawns immediately, so wait until we're inside the runtime.
+        if let Some(cmd) = self.cmd.take() {
+            self.output = Some(Box::pin(cmd.output()));
+        }
+        let polled = self
+            .output
+            .as_mut()
+            .expect("AssertFuture polled after completion")
+            .as_mut()
+            .poll(cx);
+        let output = match polled {
+            task::Poll::Ready(output) => output.unwrap(),
+            task::Poll::Pending => return task::Poll::Pending,
+        };
+        let context = self
+            .context
+            .take()
+            .expect("AssertFuture polled after completion");
+        let assert = context
+            .into_iter()
+            .fold(Assert::new(output), |assert, (name, context)| {
+                assert.append_context(name, context)
+            });
+        task::Poll::Ready(assert)
+    }
+}
+
+impl<'c> fmt::Debug for AssertFuture<'c> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AssertFuture")
+            .field("context", &self.context)
+            .finish()
+    }
+}
@@ -1,10 +1,17 @@
+extern crate assert_cmd;
+
 use std::env;
 use std::error::Error;
 use std::io;
 use std::io::Write;
 use std::process;
+use std::thread;
+use std::time;
 
 fn run() -> Result<(), Box<Error>> {
+    if let Ok(delay) = env::var("sleep") {
+        thread::sleep(time::Duration::from_millis(delay.parse()?));
+    }
     if let Ok(text) = env::var("stdout") {
         println!("{}", text);
     }
@@ -21,6 +28,7 @@ fn run() -> Result<(), Box<Error>> {
 }
 
 fn main() {
+    assert_cmd::fixture::run_fixture();
     let code = match run() {
         Ok(_) => 0,
         Err(ref e) => {
@@ -61,6 +61,7 @@
 
 use std::env;
 use std::error::Error;
+use std::ffi;
 use std::fmt;
 use std::path;
 use std::process;
@@ -93,6 +94,8 @@ where
 {
     /// Create a [`Command`] to run a specific binary of the current crate.
     ///
+    /// The binary is referenced by absolute path, so changing the [`current_dir`] still runs it.
+    ///
     /// See the [`cargo` module documentation][`cargo`] for caveats and workarounds.
     ///
     /// # Examples
@@ -120,8 +123,34 @@ where
     /// ```
     ///
     /// [`Command`]: https://doc.rust-lang.org/std/process/struct.Command.html
+    /// [`current_dir`]: https://doc.rust-lang.org/std/process/struct.Command.html#method.current_dir
     /// [`cargo`]: index.html
     fn cargo_bin<S: AsRef<str>>(name: S) -> Result<Self, CargoError>;
+
+    /// Create a [`Command`] to run a specific binary of the current crate with `args`.
+    ///
+    /// See [`cargo_bin`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// let output = Command::cargo_bin_with_args("bin_fixture", ["--flag", "v"])
+    ///     .unwrap()
+    ///     .unwrap();
+    /// println!("{:?}", output);
+    /// ```
+    ///
+    /// [`Command`]: https://doc.rust-lang.org/std/process/struct.Command.html
+    /// [`cargo_bin`]: #tymethod.cargo_bin
+    fn cargo_bin_with_args<S, I, A>(name: S, args: I) -> Result<Self, CargoError>
+    where
+        S: AsRef<str>,
+        I: IntoIterator<Item = A>,
+        A: AsRef<ffi::OsStr>;
 }
 
 impl CommandCargoExt for process::Command {
@@ -133,6 +162,17 @@ impl CommandCargoExt for process::Command {
             Err(CargoError::with_cause(NotFoundError { path }))
         }
     }
+
+    fn cargo_bin_with_args<S, I, A>(name: S, args: I) -> Result<Self, CargoError>
+    where
+        S: AsRef<str>,
+        I: IntoIterator<Item = A>,
+        A: AsRef<ffi::OsStr>,
+    {
+        let mut cmd = Self::cargo_bin(name)?;
+        cmd.args(args);
+        Ok(cmd)
+    }
 }
 
 /// Error when finding crate binary.
@@ -208,10 +248,24 @@ fn target_dir() -> path::PathBuf {
             }
             path
         })
+        .map(absolute)
         .unwrap()
 }
 
+// The path must not depend on the `Command`'s `current_dir`.
+fn absolute(path: path::PathBuf) -> path::PathBuf {
+    if path.is_absolute() {
+        path
+    } else {
+        env::current_dir().unwrap().join(path)
+    }
+}
+
 /// Look up the path to a cargo-built binary within an integration test.
+///
+/// The path is absolute, so it is unaffected by [`Command::current_dir`].
+///
+/// [`Command::current_dir`]: https://doc.rust-lang.org/std/process/struct.Command.html#method.current_dir
 pub fn cargo_bin<S: AsRef<str>>(name: S) -> path::PathBuf {
     cargo_bin_str(name.as_ref())
 }
@@ -5,6 +5,10 @@ use std::fmt;
 use std::process;
 use std::str;
 
+use predicates;
+use predicates_core::Predicate;
+use predicates_tree::CaseTreeExt;
+
 /// Converts a type to an [`OutputResult`].
 ///
 /// This is for example implemented on [`std::process::Output`].
@@ -294,28 +298,73 @@ struct Output {
 
 impl fmt::Display for Output {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        output_fmt(&self.output, f)
+        output_fmt(&self.output, None, f)
     }
 }
 
-pub(crate) fn output_fmt(output: &process::Output, f: &mut fmt::Formatter) -> fmt::Result {
+/// Render `output`, eliding the ends of lines longer than `line_width` characters.
+pub(crate) fn output_fmt(
+    output: &process::Output,
+    line_width: Option<usize>,
+    f: &mut fmt::Formatter,
+) -> fmt::Result {
     if let Some(code) = output.status.code() {
-        writeln!(f, "code={}", code)?;
+        write_code(code, f)?;
     } else {
         writeln!(f, "code=<interrupted>")?;
+        write_signal(&output.status, f)?;
     }
 
     write!(f, "stdout=```")?;
-    write_buffer(&output.stdout, f)?;
+    write_buffer(&output.stdout, line_width, f)?;
     writeln!(f, "```")?;
 
     write!(f, "stderr=```")?;
-    write_buffer(&output.stderr, f)?;
+    write_buffer(&output.stderr, line_width, f)?;
     writeln!(f, "```")?;
 
     Ok(())
 }
 
+#[cfg(unix)]
+fn write_signal(status: &process::ExitStatus, f: &mut fmt::Formatter) -> fmt::Result {
+    use std::os::unix::process::ExitStatusExt;
+    match status.signal() {
+        Some(signal) => writeln!(f, "signal={}", signal),
+        None => Ok(()),
+    }
+}
+
+#[cfg(not(unix))]
+fn write_signal(_status: &process::ExitStatus, _f: &mut fmt::Formatter) -> fmt::Result {
+    Ok(())
+}
+
+/// The `signal=` line `output_fmt` writes for `status`, if any.
+pub(crate) fn describe_signal(status: &process::ExitStatus) -> String {
+    struct Signal<'a>(&'a process::ExitStatus);
+
+    impl<'a> fmt::Display for Signal<'a> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write_signal(self.0, f)
+        }
+    }
+
+    Signal(status).to_string()
+}
+
+#[cfg(not(windows))]
+fn write_code(code: i32, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "code={}", code)
+}
+
+// Crashes are reported as `NTSTATUS` values, like `0xc0000005` for an access violation, which are
+// unreadable in decimal.
+#[cfg(windows)]
+fn write_code(code: i32, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "code={} ({:#010x})", code, code as u32)
+}
+
 pub(crate) fn dump_buffer(buffer: &[u8]) -> String {
     if let Ok(buffer) = str::from_utf8(buffer) {
         buffer.to_string()
@@ -324,12 +373,52 @@ pub(crate) fn dump_buffer(buffer: &[u8]) -> String {
     }
 }
 
-pub(crate) fn write_buffer(buffer: &[u8], f: &mut fmt::Formatter) -> fmt::Result {
-    if let Ok(buffer) = str::from_utf8(buffer) {
-        write!(f, "{}", buffer)
-    } else {
-        write!(f, "{:?}", buffer)
+/// Render the differences between two buffers for failure messages.
+pub(crate) fn diff_buffers(expected: &[u8], actual: &[u8]) -> String {
+    let expected = String::from_utf8_lossy(expected);
+    let actual = String::from_utf8_lossy(actual);
+    predicates::str::similar(expected.into_owned())
+        .find_case(false, actual.as_ref())
+        .map(|case| case.tree().to_string())
+        .unwrap_or_default()
+}
+
+pub(crate) fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
     }
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+pub(crate) fn write_buffer(
+    buffer: &[u8],
+    line_width: Option<usize>,
+    f: &mut fmt::Formatter,
+) -> fmt::Result {
+    let rendered = dump_buffer(buffer);
+    match line_width {
+        Some(line_width) => write!(f, "{}", elide_lines(&rendered, line_width)),
+        None => write!(f, "{}", rendered),
+    }
+}
+
+/// Shorten lines longer than `width` characters, noting how much was removed.
+pub(crate) fn elide_lines(text: &str, width: usize) -> String {
+    let mut elided = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches('\n');
+        let chars = content.chars().count();
+        if width < chars {
+            elided.extend(content.chars().take(width));
+            elided.push_str(&format!("\u{2026}(+{} chars)", chars - width));
+            elided.push_str(&line[content.len()..]);
+        } else {
+            elided.push_str(line);
+        }
+    }
+    elided
 }
 
 #[derive(Debug)]
@@ -345,6 +434,27 @@ impl DebugBuffer {
 
 impl fmt::Display for DebugBuffer {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write_buffer(&self.buffer, f)
+        write_buffer(&self.buffer, None, f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn elide_lines_longer_than_width() {
+        assert_eq!(
+            elide_lines("short\n0123456789\nend", 5),
+            "short\n01234\u{2026}(+5 chars)\nend"
+        );
+    }
+
+    #[test]
+    fn elide_lines_counts_chars() {
+        assert_eq!(
+            elide_lines("\u{e9}\u{e9}\u{e9}\n", 2),
+            "\u{e9}\u{e9}\u{2026}(+1 chars)\n"
+        );
     }
 }
@@ -0,0 +1,290 @@
+//! Rerun the current executable as a fixture, rather than building a separate binary.
+//!
+//! [`CommandFixtureExt`] creates a [`Command`] for the current executable, marked by the
+//! [`FIXTURE_ENV`] environment variable.  The executable checks [`is_fixture`] on start up to
+//! switch to the fixture behavior.
+//!
+//! # Examples
+//!
+//! With the default test harness, select a single test to act as the fixture, passing
+//! `--nocapture` so its output isn't captured and exiting before the harness reports:
+//!
+//! ```rust,no_run
+//! use assert_cmd::fixture;
+//! use assert_cmd::prelude::*;
+//!
+//! use std::process;
+//! use std::process::Command;
+//!
+//! fn fixture_main() {
+//!     if !fixture::is_fixture() {
+//!         return;
+//!     }
+//!     println!("hello");
+//!     process::exit(0);
+//! }
+//!
+//! Command::current_exe_as_fixture()
+//!     .unwrap()
+//!     .args(["--exact", "fixture_main", "--nocapture"])
+//!     .assert()
+//!     .stdout("hello\n");
+//! ```
+//!
+//! With `harness = false`, check [`is_fixture`] at the top of `main` instead.
+//!
+//! The default harness prints its own `running 1 test` banner to stdout before the fixture runs,
+//! so assert on the end of stdout, like with `stdout_ends_with`, or use `harness = false` for
+//! exact output.
+//!
+//! [`Fixture`] instead configures the fixture by `ASSERT_CMD_FIXTURE_*` environment variables,
+//! which [`run_fixture`] acts on, so the test selected as the fixture only needs to call it.
+//!
+//! [`CommandFixtureExt`]: trait.CommandFixtureExt.html
+//! [`FIXTURE_ENV`]: constant.FIXTURE_ENV.html
+//! [`is_fixture`]: fn.is_fixture.html
+//! [`run_fixture`]: fn.run_fixture.html
+//! [`Fixture`]: struct.Fixture.html
+//! [`Command`]: https://doc.rust-lang.org/std/process/struct.Command.html
+
+use std::env;
+use std::ffi;
+use std::io;
+use std::process;
+use std::thread;
+use std::time;
+
+/// Environment variable marking a process as running as a fixture.
+pub const FIXTURE_ENV: &str = "ASSERT_CMD_FIXTURE";
+
+/// Environment variables configuring [`run_fixture`], set by [`Fixture`].
+const STDOUT_ENV: &str = "ASSERT_CMD_FIXTURE_STDOUT";
+const STDERR_ENV: &str = "ASSERT_CMD_FIXTURE_STDERR";
+const EXIT_ENV: &str = "ASSERT_CMD_FIXTURE_EXIT";
+const SLEEP_ENV: &str = "ASSERT_CMD_FIXTURE_SLEEP";
+
+/// Create a [`Command`] rerunning the current executable as a fixture.
+///
+/// See the [`fixture` module documentation][`fixture`] for how to dispatch to the fixture.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use assert_cmd::prelude::*;
+///
+/// use std::process::Command;
+///
+/// let mut cmd = Command::current_exe_as_fixture().unwrap();
+/// cmd.args(["--exact", "fixture_main", "--nocapture"]);
+/// ```
+///
+/// [`Command`]: https://doc.rust-lang.org/std/process/struct.Command.html
+/// [`fixture`]: index.html
+pub trait CommandFixtureExt
+where
+    Self: Sized,
+{
+    /// Create a [`Command`] for the current executable with [`FIXTURE_ENV`] set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// let mut cmd = Command::current_exe_as_fixture().unwrap();
+    /// cmd.args(["--exact", "fixture_main", "--nocapture"]);
+    /// ```
+    ///
+    /// [`Command`]: https://doc.rust-lang.org/std/process/struct.Command.html
+    /// [`FIXTURE_ENV`]: constant.FIXTURE_ENV.html
+    fn current_exe_as_fixture() -> io::Result<Self>;
+}
+
+impl CommandFixtureExt for process::Command {
+    fn current_exe_as_fixture() -> io::Result<Self> {
+        let mut cmd = process::Command::new(env::current_exe()?);
+        cmd.env(FIXTURE_ENV, "1");
+        Ok(cmd)
+    }
+}
+
+/// Whether the current process was started by [`current_exe_as_fixture`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use assert_cmd::fixture;
+///
+/// if fixture::is_fixture() {
+///     println!("hello");
+/// }
+/// ```
+///
+/// [`current_exe_as_fixture`]: trait.CommandFixtureExt.html#tymethod.current_exe_as_fixture
+pub fn is_fixture() -> bool {
+    env::var_os(FIXTURE_ENV).is_some()
+}
+
+/// Act as the fixture configured by a [`Fixture`], if the current process is one.
+///
+/// When [`is_fixture`], sleeps for `ASSERT_CMD_FIXTURE_SLEEP` milliseconds, prints
+/// `ASSERT_CMD_FIXTURE_STDOUT` and `ASSERT_CMD_FIXTURE_STDERR`, each followed by a newline, and
+/// exits with `ASSERT_CMD_FIXTURE_EXIT`, never returning.  Otherwise, returns immediately.
+///
+/// # Panics
+///
+/// If the sleep or exit code isn't a number.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use assert_cmd::fixture;
+///
+/// fn fixture_main() {
+///     fixture::run_fixture();
+/// }
+/// ```
+///
+/// [`Fixture`]: struct.Fixture.html
+/// [`is_fixture`]: fn.is_fixture.html
+pub fn run_fixture() {
+    if !is_fixture() {
+        return;
+    }
+    if let Ok(delay) = env::var(SLEEP_ENV) {
+        let delay = delay
+            .parse()
+            .unwrap_or_else(|_| panic!("Invalid `{}`, expected milliseconds", SLEEP_ENV));
+        thread::sleep(time::Duration::from_millis(delay));
+    }
+    if let Ok(text) = env::var(STDOUT_ENV) {
+        println!("{}", text);
+    }
+    if let Ok(text) = env::var(STDERR_ENV) {
+        eprintln!("{}", text);
+    }
+    let code = env::var(EXIT_ENV)
+        .map(|code| {
+            code.parse()
+                .unwrap_or_else(|_| panic!("Invalid `{}`, expected a code", EXIT_ENV))
+        })
+        .unwrap_or(0);
+    process::exit(code);
+}
+
+/// Build a [`Command`] for a fixture, with its output and exit code set.
+///
+/// [`current_exe`] reruns the current executable, which must call [`run_fixture`].  When it is a
+/// test binary using the default harness, the harness prints its own `running 1 test` banner to
+/// stdout first, so only the end of stdout is exactly as set.  [`command_for`] runs another
+/// binary calling [`run_fixture`], like one with `harness = false`, whose stdout is exact.
+///
+/// The fixture prints `stdout` and `stderr`, each followed by a newline, after sleeping for
+/// `sleep`, and then exits with `exit`.  Unset fields are left to the defaults: no output, no
+/// delay and exit code `0`.  They are passed as `ASSERT_CMD_FIXTURE_*` environment variables,
+/// which [`run_fixture`] acts on.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use assert_cmd::fixture;
+/// use assert_cmd::fixture::Fixture;
+/// use assert_cmd::prelude::*;
+///
+/// fn fixture_main() {
+///     fixture::run_fixture();
+/// }
+///
+/// Fixture::new()
+///     .stdout("hi")
+///     .stderr("err")
+///     .exit(2)
+///     .current_exe()
+///     .unwrap()
+///     .args(["--exact", "fixture_main", "--nocapture"])
+///     .assert()
+///     .code(2)
+///     .stdout_ends_with("hi\n")
+///     .stderr("err\n");
+/// ```
+///
+/// [`Command`]: https://doc.rust-lang.org/std/process/struct.Command.html
+/// [`current_exe`]: #method.current_exe
+/// [`command_for`]: #method.command_for
+/// [`run_fixture`]: fn.run_fixture.html
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Fixture {
+    stdout: Option<String>,
+    stderr: Option<String>,
+    exit: Option<i32>,
+    sleep: Option<time::Duration>,
+}
+
+impl Fixture {
+    /// Create a `Fixture` with no output, exiting successfully.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Print `text` and a newline to `stdout`.
+    pub fn stdout<S: Into<String>>(mut self, text: S) -> Self {
+        self.stdout = Some(text.into());
+        self
+    }
+
+    /// Print `text` and a newline to `stderr`.
+    pub fn stderr<S: Into<String>>(mut self, text: S) -> Self {
+        self.stderr = Some(text.into());
+        self
+    }
+
+    /// Exit with `code`.
+    pub fn exit(mut self, code: i32) -> Self {
+        self.exit = Some(code);
+        self
+    }
+
+    /// Sleep for `delay` before writing any output.
+    pub fn sleep(mut self, delay: time::Duration) -> Self {
+        self.sleep = Some(delay);
+        self
+    }
+
+    /// Create a [`Command`] rerunning the current executable as the fixture.
+    ///
+    /// The executable must call [`run_fixture`], see [`current_exe_as_fixture`].
+    ///
+    /// [`Command`]: https://doc.rust-lang.org/std/process/struct.Command.html
+    /// [`run_fixture`]: fn.run_fixture.html
+    /// [`current_exe_as_fixture`]: trait.CommandFixtureExt.html#tymethod.current_exe_as_fixture
+    pub fn current_exe(&self) -> io::Result<process::Command> {
+        Ok(self.command_for(env::current_exe()?))
+    }
+
+    /// Create a [`Command`] running the binary at `program` as the fixture.
+    ///
+    /// The binary must call [`run_fixture`], [`FIXTURE_ENV`] is set for it.
+    ///
+    /// [`Command`]: https://doc.rust-lang.org/std/process/struct.Command.html
+    /// [`run_fixture`]: fn.run_fixture.html
+    /// [`FIXTURE_ENV`]: constant.FIXTURE_ENV.html
+    pub fn command_for<S: AsRef<ffi::OsStr>>(&self, program: S) -> process::Command {
+        let mut cmd = process::Command::new(program);
+        cmd.env(FIXTURE_ENV, "1");
+        if let Some(ref text) = self.stdout {
+            cmd.env(STDOUT_ENV, text);
+        }
+        if let Some(ref text) = self.stderr {
+            cmd.env(STDERR_ENV, text);
+        }
+        if let Some(code) = self.exit {
+            cmd.env(EXIT_ENV, code.to_string());
+        }
+        if let Some(delay) = self.sleep {
+            cmd.env(SLEEP_ENV, delay.as_millis().to_string());
+        }
+        cmd
+    }
+}
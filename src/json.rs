@@ -0,0 +1,99 @@
+//! Navigate JSON output.
+
+use serde_json;
+
+/// Look up the value at `path` within `value`.
+///
+/// `path` is a small subset of JSONPath: dotted keys and array indices, like `.items[0].name`.  A
+/// leading `$` is optional.
+///
+/// Returns `Ok(None)` if nothing is at `path` and `Err` if `path` is malformed.
+pub(crate) fn resolve<'v>(
+    value: &'v serde_json::Value,
+    path: &str,
+) -> Result<Option<&'v serde_json::Value>, String> {
+    let mut current = value;
+    for segment in parse(path)? {
+        let next = match segment {
+            Segment::Key(key) => current.get(key),
+            Segment::Index(index) => current.get(index),
+        };
+        current = match next {
+            Some(next) => next,
+            None => return Ok(None),
+        };
+    }
+    Ok(Some(current))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Segment<'p> {
+    Key(&'p str),
+    Index(usize),
+}
+
+fn parse(path: &str) -> Result<Vec<Segment<'_>>, String> {
+    let mut rest = path.trim_start_matches('$');
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        if rest.starts_with('[') {
+            let end = rest
+                .find(']')
+                .ok_or_else(|| format!("Unclosed `[` in JSON path `{}`", path))?;
+            let index = rest[1..end].parse().map_err(|_| {
+                format!("Invalid index `{}` in JSON path `{}`", &rest[1..end], path)
+            })?;
+            segments.push(Segment::Index(index));
+            rest = &rest[end + 1..];
+        } else {
+            if rest.starts_with('.') {
+                rest = &rest[1..];
+            }
+            let end = rest.find(&['.', '['][..]).unwrap_or(rest.len());
+            if end == 0 {
+                return Err(format!("Empty key in JSON path `{}`", path));
+            }
+            segments.push(Segment::Key(&rest[..end]));
+            rest = &rest[end..];
+        }
+    }
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_keys_and_indices() {
+        assert_eq!(
+            parse("$.items[0].name").unwrap(),
+            vec![
+                Segment::Key("items"),
+                Segment::Index(0),
+                Segment::Key("name")
+            ]
+        );
+        assert_eq!(parse("version").unwrap(), vec![Segment::Key("version")]);
+        assert_eq!(parse("").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn parse_malformed() {
+        assert!(parse("items[0").is_err());
+        assert!(parse("items[x]").is_err());
+        assert!(parse("items..name").is_err());
+    }
+
+    #[test]
+    fn resolve_nested() {
+        let value: serde_json::Value =
+            serde_json::from_str(r#"{"items": [{"name": "a"}, {"name": "b"}]}"#).unwrap();
+        assert_eq!(
+            resolve(&value, ".items[1].name").unwrap(),
+            Some(&serde_json::Value::from("b"))
+        );
+        assert_eq!(resolve(&value, ".items[2].name").unwrap(), None);
+        assert_eq!(resolve(&value, ".missing").unwrap(), None);
+    }
+}
@@ -2,7 +2,8 @@
 //!
 //! `assert_cmd` includes support for:
 //! - Setting up your program-under-test (see [`CommandCargoExt`], [`CommandStdInExt`]).
-//! - Verifying your program-under-test (see [`OutputOkExt`], [`OutputAssertExt`]).
+//! - Verifying your program-under-test (see [`OutputOkExt`], [`OutputAssertExt`],
+//!   [`CommandRunExt`]).
 //!
 //! ```toml
 //! [dependencies]
@@ -14,6 +15,8 @@
 //! Create a [`Command`]:
 //! - `Command::new(path)`, see [`Command`]
 //! - `Command::cargo_bin(name)`, see [`CommandCargoExt`]
+//! - `Command::current_exe_as_fixture()`, see [`CommandFixtureExt`]
+//! - `Fixture::new().stdout(..).exit(..).current_exe()`, see [`Fixture`]
 //!
 //! Configure a [`Command`]:
 //! - `arg` / `args`, see [`Command`]
@@ -30,6 +33,46 @@
 //!   - `code`, see [`Assert`]
 //!   - `stdout`, see [`Assert`]
 //!   - `stderr`, see [`Assert`]
+//!   - `stdout_json_field` with the `json` feature, see [`Assert`]
+//!   - `stdout_csv_headers` and `stdout_csv_rows` with the `csv` feature, see [`Assert`]
+//!   - `stdout_json_schema` with the `jsonschema` feature (Rust 1.88), see [`Assert`]
+//!   - `stdout_toml` with the `toml` feature and `stdout_yaml` with the `yaml` feature, see
+//!     [`Assert`]
+//!   - `assert_cmd_snapshot!` with the `insta` feature, see [`Assert::to_snapshot`]
+//!   - `to_report` with the `serde` feature, see [`Assert`]
+//!   - CLI-tailored predicates, like `lines_eq`, see [`predicate`]
+//! - `assert` for `tokio::process::Command` with the `tokio` feature, see
+//!   [`AsyncOutputAssertExt`]
+//!
+//! Run a [`Command`] under controlled conditions:
+//! - `assert_does_not_leak_env`, see [`CommandRunExt`]
+//! - `assert_within` and `with_default_timeout`, see [`CommandRunExt`]
+//! - `completes_within`, see [`CommandRunExt`]
+//! - `send_signal_then_assert`, see [`CommandRunExt`]
+//! - `terminates_within`, see [`CommandRunExt`]
+//! - `stdin_eof_then_assert`, see [`CommandRunExt`]
+//! - `assert_atomic_write`, see [`CommandRunExt`]
+//! - `assert_locale_stable`, see [`CommandRunExt`]
+//! - `assert_stable_size`, see [`CommandRunExt`]
+//! - `assert_reproducible_with_epoch`, see [`CommandRunExt`]
+//! - `assert_matches_command` and `assert_matches_shell`, see [`CommandRunExt`]
+//! - `assert_quieter_than`, see [`CommandRunExt`]
+//! - `assert_dash_is_stdout`, see [`CommandRunExt`]
+//! - `assert_format_idempotent`, see [`CommandRunExt`]
+//! - `stdin::assert_roundtrip`, piping input through an encoder and decoder
+//! - `assert_help` and `assert_version`, see [`CommandRunExt`]
+//! - `assert_args_after_double_dash`, see [`CommandRunExt`]
+//! - `assert_with_fds`, see [`CommandRunExt`]
+//! - `assert_with_max_rss`, see [`CommandRunExt`]
+//! - `assert_combined`, see [`CommandRunExt`]
+//! - `assert_with_process_group`, see [`CommandRunExt`]
+//! - `assert_concurrent`, see [`CommandRunExt`]
+//! - `for_each_stdout_line`, see [`CommandRunExt`]
+//! - `assert_no_writes_outside`, see [`CommandRunExt`]
+//! - `assert_dry_run_clean`, see [`CommandRunExt`]
+//! - `no_color` and `force_color`, see [`CommandRunExt`]
+//! - `with_path` and `prepend_path`, see [`CommandRunExt`]
+//! - `with_config_file` with the `tempfile` feature (Rust 1.85), see [`CommandRunExt`]
 //!
 //! ## Examples
 //!
@@ -100,17 +143,47 @@
 //! [`Command`]: https://doc.rust-lang.org/std/process/struct.Command.html
 //! [`Assert`]: assert/struct.Assert.html
 //! [`success()`]: assert/struct.Assert.html#method.success
+//! [`Assert::to_snapshot`]: assert/struct.Assert.html#method.to_snapshot
 //! [`CommandCargoExt`]: cargo/trait.CommandCargoExt.html
+//! [`CommandFixtureExt`]: fixture/trait.CommandFixtureExt.html
+//! [`Fixture`]: fixture/struct.Fixture.html
 //! [`CommandStdInExt`]: stdin/trait.CommandStdInExt.html
 //! [`OutputOkExt`]: cmd/trait.OutputOkExt.html
 //! [`OutputAssertExt`]: assert/trait.OutputAssertExt.html
+//! [`CommandRunExt`]: run/trait.CommandRunExt.html
+//! [`predicate`]: predicate/index.html
+//! [`AsyncOutputAssertExt`]: async_assert/trait.AsyncOutputAssertExt.html
 
 #![warn(missing_docs)]
 
+#[cfg(feature = "csv")]
+extern crate csv;
+extern crate difference;
 extern crate escargot;
+#[cfg(feature = "insta")]
+#[doc(hidden)]
+pub extern crate insta_crate as insta;
+#[cfg(feature = "jsonschema")]
+extern crate jsonschema_crate as jsonschema;
+#[cfg(unix)]
+extern crate libc;
 extern crate predicates;
 extern crate predicates_core;
 extern crate predicates_tree;
+#[cfg(feature = "regex")]
+extern crate regex;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(any(feature = "json", feature = "jsonschema"))]
+extern crate serde_json;
+#[cfg(feature = "yaml")]
+extern crate serde_yaml;
+#[cfg(feature = "tempfile")]
+extern crate tempfile;
+#[cfg(feature = "tokio")]
+extern crate tokio;
+#[cfg(feature = "toml")]
+extern crate toml;
 
 /// Allows you to pull the name from your Cargo.toml at compile time.
 ///
@@ -146,16 +219,148 @@ macro_rules! crate_name {
     };
 }
 
+/// Snapshot the output of an [`Assert`] with [`insta`].
+///
+/// The output is rendered with [`Assert::to_snapshot`], applying any
+/// [`Assert::snapshot_filter`]s.  This is a macro, rather than a method, so `insta` stores the
+/// snapshot alongside the calling test.
+///
+/// Requires the `insta` feature, which also enables [`Assert::snapshot_filter`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate assert_cmd;
+///
+/// use std::process::Command;
+/// use assert_cmd::prelude::*;
+///
+/// fn main() {
+///     let assert = Command::cargo_bin("bin_fixture")
+///         .unwrap()
+///         .env("stdout", "hello")
+///         .assert()
+///         .success();
+///     assert_cmd_snapshot!("hello", assert);
+/// }
+/// ```
+///
+/// [`insta`]: https://docs.rs/insta
+/// [`Assert`]: assert/struct.Assert.html
+/// [`Assert::to_snapshot`]: assert/struct.Assert.html#method.to_snapshot
+/// [`Assert::snapshot_filter`]: assert/struct.Assert.html#method.snapshot_filter
+#[cfg(feature = "insta")]
+#[macro_export]
+macro_rules! assert_cmd_snapshot {
+    ($name:expr, $assert:expr) => {
+        $crate::insta::assert_snapshot!($name, $assert.to_snapshot())
+    };
+}
+
+/// Run a table of cases against a command.
+///
+/// The command expression is evaluated for each case.  Each case is named, may configure the
+/// command with `env: { key => value, ... }` and `args: [arg, ...]`, and may check `code`,
+/// `stdout`, and `stderr` using the same short-hands as [`Assert`].  The case's name is attached
+/// as context to any failure.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate assert_cmd;
+///
+/// use std::process::Command;
+/// use assert_cmd::prelude::*;
+///
+/// fn main() {
+///     assert_cmd_cases! {
+///         Command::cargo_bin("bin_fixture").unwrap();
+///         case("default", code: 0, stdout: ""),
+///         case("hello", env: { "stdout" => "hello" }, code: 0, stdout: "hello\n"),
+///         case("fail", env: { "exit" => "42", "stderr" => "oops" }, code: 42, stderr: "oops\n"),
+///     }
+/// }
+/// ```
+///
+/// [`Assert`]: assert/struct.Assert.html
+#[macro_export]
+macro_rules! assert_cmd_cases {
+    ($cmd:expr; $(case($name:expr $(, $($field:tt)*)?)),* $(,)*) => {
+        $({
+            let mut cmd = $cmd;
+            $crate::assert_cmd_cases!(@setup cmd; $($($field)*)?,);
+            let assert = $crate::prelude::OutputAssertExt::assert(&mut cmd)
+                .append_context("case", $name);
+            $crate::assert_cmd_cases!(@check assert; $($($field)*)?,);
+        })*
+    };
+
+    (@setup $cmd:ident;) => {};
+    (@setup $cmd:ident; , $($rest:tt)*) => {
+        $crate::assert_cmd_cases!(@setup $cmd; $($rest)*);
+    };
+    (@setup $cmd:ident; env: { $($key:expr => $value:expr),* $(,)* }, $($rest:tt)*) => {
+        $($cmd.env($key, $value);)*
+        $crate::assert_cmd_cases!(@setup $cmd; $($rest)*);
+    };
+    (@setup $cmd:ident; args: [ $($arg:expr),* $(,)* ], $($rest:tt)*) => {
+        $($cmd.arg($arg);)*
+        $crate::assert_cmd_cases!(@setup $cmd; $($rest)*);
+    };
+    (@setup $cmd:ident; $field:ident: $value:expr, $($rest:tt)*) => {
+        $crate::assert_cmd_cases!(@setup $cmd; $($rest)*);
+    };
+
+    (@check $assert:ident;) => {};
+    (@check $assert:ident; , $($rest:tt)*) => {
+        $crate::assert_cmd_cases!(@check $assert; $($rest)*);
+    };
+    (@check $assert:ident; env: { $($ignore:tt)* }, $($rest:tt)*) => {
+        $crate::assert_cmd_cases!(@check $assert; $($rest)*);
+    };
+    (@check $assert:ident; args: [ $($ignore:tt)* ], $($rest:tt)*) => {
+        $crate::assert_cmd_cases!(@check $assert; $($rest)*);
+    };
+    (@check $assert:ident; code: $value:expr, $($rest:tt)*) => {
+        let $assert = $assert.code($value);
+        $crate::assert_cmd_cases!(@check $assert; $($rest)*);
+    };
+    (@check $assert:ident; stdout: $value:expr, $($rest:tt)*) => {
+        let $assert = $assert.stdout($value);
+        $crate::assert_cmd_cases!(@check $assert; $($rest)*);
+    };
+    (@check $assert:ident; stderr: $value:expr, $($rest:tt)*) => {
+        let $assert = $assert.stderr($value);
+        $crate::assert_cmd_cases!(@check $assert; $($rest)*);
+    };
+}
+
 pub mod assert;
+#[cfg(feature = "tokio")]
+pub mod async_assert;
 pub mod cargo;
 pub mod cmd;
+pub mod fixture;
+#[cfg(feature = "json")]
+mod json;
+pub mod predicate;
+#[cfg(feature = "serde")]
+pub mod report;
+pub mod run;
+mod spawn;
 pub mod stdin;
 
 /// Extension traits that are useful to have available.
 pub mod prelude {
     pub use assert::OutputAssertExt;
+    #[cfg(feature = "tokio")]
+    pub use async_assert::AsyncOutputAssertExt;
     pub use cargo::CommandCargoExt;
     pub use cmd::OutputOkExt;
+    pub use fixture::CommandFixtureExt;
+    pub use run::CommandRunExt;
     pub use stdin::CommandStdInExt;
 }
 
@@ -0,0 +1,615 @@
+//! [Predicates][predicates] tailored to CLI output.
+//!
+//! These are usable anywhere an [`IntoOutputPredicate`] is accepted.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use assert_cmd::prelude::*;
+//!
+//! use std::process::Command;
+//!
+//! Command::cargo_bin("bin_fixture")
+//!     .unwrap()
+//!     .env("stdout", "hello")
+//!     .assert()
+//!     .stdout(assert_cmd::predicate::lines_eq("hello\n"));
+//! ```
+//!
+//! Reusable expectations can be kept as boxed predicates, like
+//! `predicates::BoxPredicate<[u8]>`, and labeled with [`named`] so failures say which one failed.
+//!
+//! [predicates]: https://docs.rs/predicates
+//! [`IntoOutputPredicate`]: ../assert/trait.IntoOutputPredicate.html
+//! [`named`]: fn.named.html
+
+use std::fmt;
+
+use difference;
+#[cfg(feature = "jsonschema")]
+use jsonschema;
+use predicates_core;
+#[cfg(feature = "jsonschema")]
+use serde_json;
+#[cfg(feature = "yaml")]
+use serde_yaml;
+#[cfg(feature = "toml")]
+use toml;
+
+/// Lines of context shown around each change.
+const CONTEXT: usize = 3;
+
+/// Create a [Predicate] comparing output to `expected` line by line.
+///
+/// On failure, a compact unified diff of the lines is shown, rather than a character-level diff.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use assert_cmd::prelude::*;
+///
+/// use std::process::Command;
+///
+/// Command::cargo_bin("bin_fixture")
+///     .unwrap()
+///     .env("stdout", "hello")
+///     .assert()
+///     .stdout(assert_cmd::predicate::lines_eq("hello\n"));
+/// ```
+///
+/// [Predicate]: https://docs.rs/predicates-core/1.0.0/predicates_core/trait.Predicate.html
+pub fn lines_eq<S>(expected: S) -> LinesEqPredicate
+where
+    S: Into<String>,
+{
+    LinesEqPredicate {
+        expected: expected.into(),
+    }
+}
+
+/// [Predicate] comparing output to an expected value line by line.
+///
+/// Create a `LinesEqPredicate` through [`lines_eq`].
+///
+/// [Predicate]: https://docs.rs/predicates-core/1.0.0/predicates_core/trait.Predicate.html
+/// [`lines_eq`]: fn.lines_eq.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinesEqPredicate {
+    expected: String,
+}
+
+impl predicates_core::reflection::PredicateReflection for LinesEqPredicate {}
+
+impl predicates_core::Predicate<str> for LinesEqPredicate {
+    fn eval(&self, variable: &str) -> bool {
+        self.expected == variable
+    }
+
+    fn find_case<'a>(
+        &'a self,
+        expected: bool,
+        variable: &str,
+    ) -> Option<predicates_core::reflection::Case<'a>> {
+        let result = self.eval(variable);
+        if result != expected {
+            return None;
+        }
+        let mut case = predicates_core::reflection::Case::new(Some(self), result);
+        if !result {
+            let diff = unified_diff(&self.expected, variable);
+            case = case.add_product(predicates_core::reflection::Product::new("diff", diff));
+        }
+        Some(case)
+    }
+}
+
+impl fmt::Display for LinesEqPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "var lines == expected lines")
+    }
+}
+
+/// Label the output [Predicate] `pred` with `name`, for failures to show which expectation
+/// failed.
+///
+/// `pred` is commonly a boxed predicate shared between tests.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// extern crate assert_cmd;
+/// extern crate predicates;
+///
+/// use assert_cmd::prelude::*;
+///
+/// use predicates::prelude::*;
+/// use predicates::BoxPredicate;
+/// use std::process::Command;
+///
+/// let greeting = BoxPredicate::new(predicate::str::contains("hello").from_utf8());
+/// Command::cargo_bin("bin_fixture")
+///     .unwrap()
+///     .env("stdout", "hello")
+///     .assert()
+///     .stdout(assert_cmd::predicate::named("greeting", greeting));
+/// ```
+///
+/// [Predicate]: https://docs.rs/predicates-core/1.0.0/predicates_core/trait.Predicate.html
+pub fn named<S, P>(name: S, pred: P) -> NamedOutputPredicate<P>
+where
+    S: Into<String>,
+    P: predicates_core::Predicate<[u8]>,
+{
+    NamedOutputPredicate {
+        name: name.into(),
+        pred,
+    }
+}
+
+/// Output [Predicate] labeled with a name.
+///
+/// Create a `NamedOutputPredicate` through [`named`].
+///
+/// [Predicate]: https://docs.rs/predicates-core/1.0.0/predicates_core/trait.Predicate.html
+/// [`named`]: fn.named.html
+#[derive(Debug, Clone)]
+pub struct NamedOutputPredicate<P> {
+    name: String,
+    pred: P,
+}
+
+impl<P> predicates_core::reflection::PredicateReflection for NamedOutputPredicate<P>
+where
+    P: predicates_core::Predicate<[u8]>,
+{
+    fn children<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = predicates_core::reflection::Child<'a>> + 'a> {
+        let children = vec![predicates_core::reflection::Child::new(
+            &self.name, &self.pred,
+        )];
+        Box::new(children.into_iter())
+    }
+}
+
+impl<P> predicates_core::Predicate<[u8]> for NamedOutputPredicate<P>
+where
+    P: predicates_core::Predicate<[u8]>,
+{
+    fn eval(&self, variable: &[u8]) -> bool {
+        self.pred.eval(variable)
+    }
+
+    fn find_case<'a>(
+        &'a self,
+        expected: bool,
+        variable: &[u8],
+    ) -> Option<predicates_core::reflection::Case<'a>> {
+        self.pred.find_case(expected, variable).map(|child| {
+            predicates_core::reflection::Case::new(Some(self), expected).add_child(child)
+        })
+    }
+}
+
+impl<P> fmt::Display for NamedOutputPredicate<P>
+where
+    P: predicates_core::Predicate<[u8]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.name, self.pred)
+    }
+}
+
+/// Create a [Predicate] validating JSON output against the [JSON Schema] `schema`.
+///
+/// On failure, each validation error is shown with the path to the invalid value.  Requires the
+/// `jsonschema` feature, which needs Rust 1.88.
+///
+/// # Panics
+///
+/// If `schema` is not a valid JSON Schema.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use assert_cmd::prelude::*;
+///
+/// use std::process::Command;
+///
+/// Command::cargo_bin("bin_fixture")
+///     .unwrap()
+///     .env("stdout", r#"{"version": "1.2.3"}"#)
+///     .assert()
+///     .stdout(assert_cmd::predicate::json_schema(
+///         r#"{"type": "object", "required": ["version"]}"#,
+///     ));
+/// ```
+///
+/// [Predicate]: https://docs.rs/predicates-core/1.0.0/predicates_core/trait.Predicate.html
+/// [JSON Schema]: https://json-schema.org/
+#[cfg(feature = "jsonschema")]
+pub fn json_schema(schema: &str) -> JsonSchemaPredicate {
+    let parsed: serde_json::Value = serde_json::from_str(schema)
+        .unwrap_or_else(|err| panic!("Invalid JSON schema, not JSON: {}", err));
+    let validator = jsonschema::validator_for(&parsed)
+        .unwrap_or_else(|err| panic!("Invalid JSON schema: {}", err));
+    JsonSchemaPredicate {
+        schema: schema.to_owned(),
+        validator,
+    }
+}
+
+/// [Predicate] validating JSON output against a JSON Schema.
+///
+/// Create a `JsonSchemaPredicate` through [`json_schema`].
+///
+/// [Predicate]: https://docs.rs/predicates-core/1.0.0/predicates_core/trait.Predicate.html
+/// [`json_schema`]: fn.json_schema.html
+#[cfg(feature = "jsonschema")]
+#[derive(Debug, Clone)]
+pub struct JsonSchemaPredicate {
+    schema: String,
+    validator: jsonschema::Validator,
+}
+
+#[cfg(feature = "jsonschema")]
+impl JsonSchemaPredicate {
+    /// Describe why `variable` is invalid, if it is.
+    fn errors(&self, variable: &[u8]) -> Vec<String> {
+        let instance: serde_json::Value = match serde_json::from_slice(variable) {
+            Ok(instance) => instance,
+            Err(err) => return vec![format!("invalid JSON: {}", err)],
+        };
+        self.validator
+            .iter_errors(&instance)
+            .map(|err| {
+                let path = err.instance_path().to_string();
+                let path = if path.is_empty() {
+                    "/".to_owned()
+                } else {
+                    path
+                };
+                format!("{}: {}", path, err)
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "jsonschema")]
+impl predicates_core::reflection::PredicateReflection for JsonSchemaPredicate {
+    fn parameters<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = predicates_core::reflection::Parameter<'a>> + 'a> {
+        let params = vec![predicates_core::reflection::Parameter::new(
+            "schema",
+            &self.schema,
+        )];
+        Box::new(params.into_iter())
+    }
+}
+
+#[cfg(feature = "jsonschema")]
+impl predicates_core::Predicate<[u8]> for JsonSchemaPredicate {
+    fn eval(&self, variable: &[u8]) -> bool {
+        self.errors(variable).is_empty()
+    }
+
+    fn find_case<'a>(
+        &'a self,
+        expected: bool,
+        variable: &[u8],
+    ) -> Option<predicates_core::reflection::Case<'a>> {
+        let errors = self.errors(variable);
+        let result = errors.is_empty();
+        if result != expected {
+            return None;
+        }
+        let mut case = predicates_core::reflection::Case::new(Some(self), result);
+        for error in errors {
+            case = case.add_product(predicates_core::reflection::Product::new("error", error));
+        }
+        Some(case)
+    }
+}
+
+#[cfg(feature = "jsonschema")]
+impl fmt::Display for JsonSchemaPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "var matches JSON schema")
+    }
+}
+
+/// Create a [Predicate] comparing TOML output to `expected` structurally.
+///
+/// Formatting and key order don't matter.  On failure, the parse error or a diff of the values is
+/// shown.  Requires the `toml` feature.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// extern crate assert_cmd;
+/// extern crate toml;
+///
+/// use assert_cmd::prelude::*;
+///
+/// use std::process::Command;
+///
+/// let expected: toml::Value = toml::from_str("name = \"bin\"").unwrap();
+/// Command::cargo_bin("bin_fixture")
+///     .unwrap()
+///     .env("stdout", "name   =   'bin'")
+///     .assert()
+///     .stdout(assert_cmd::predicate::toml_eq(expected));
+/// ```
+///
+/// [Predicate]: https://docs.rs/predicates-core/1.0.0/predicates_core/trait.Predicate.html
+#[cfg(feature = "toml")]
+pub fn toml_eq(expected: toml::Value) -> TomlEqPredicate {
+    TomlEqPredicate {
+        rendered: expected.to_string(),
+        expected,
+    }
+}
+
+/// [Predicate] comparing TOML output to an expected value.
+///
+/// Create a `TomlEqPredicate` through [`toml_eq`].
+///
+/// [Predicate]: https://docs.rs/predicates-core/1.0.0/predicates_core/trait.Predicate.html
+/// [`toml_eq`]: fn.toml_eq.html
+#[cfg(feature = "toml")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TomlEqPredicate {
+    expected: toml::Value,
+    rendered: String,
+}
+
+#[cfg(feature = "toml")]
+impl TomlEqPredicate {
+    /// Describe how `variable` differs from the expected value, if it does.
+    fn compare(&self, variable: &[u8]) -> Result<(), (&'static str, String)> {
+        let actual: toml::Value = toml::from_slice(variable)
+            .map_err(|err| ("error", format!("invalid TOML: {}", err)))?;
+        if actual != self.expected {
+            return Err(("diff", unified_diff(&self.rendered, &actual.to_string())));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "toml")]
+impl predicates_core::reflection::PredicateReflection for TomlEqPredicate {
+    fn parameters<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = predicates_core::reflection::Parameter<'a>> + 'a> {
+        let params = vec![predicates_core::reflection::Parameter::new(
+            "expected",
+            &self.rendered,
+        )];
+        Box::new(params.into_iter())
+    }
+}
+
+#[cfg(feature = "toml")]
+impl predicates_core::Predicate<[u8]> for TomlEqPredicate {
+    fn eval(&self, variable: &[u8]) -> bool {
+        self.compare(variable).is_ok()
+    }
+
+    fn find_case<'a>(
+        &'a self,
+        expected: bool,
+        variable: &[u8],
+    ) -> Option<predicates_core::reflection::Case<'a>> {
+        structural_case(self, expected, self.compare(variable))
+    }
+}
+
+#[cfg(feature = "toml")]
+impl fmt::Display for TomlEqPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "var == expected TOML")
+    }
+}
+
+/// Create a [Predicate] comparing YAML output to `expected` structurally.
+///
+/// Formatting and key order don't matter.  On failure, the parse error or a diff of the values is
+/// shown.  Requires the `yaml` feature.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// extern crate assert_cmd;
+/// extern crate serde_yaml;
+///
+/// use assert_cmd::prelude::*;
+///
+/// use std::process::Command;
+///
+/// let expected: serde_yaml::Value = serde_yaml::from_str("name: bin").unwrap();
+/// Command::cargo_bin("bin_fixture")
+///     .unwrap()
+///     .env("stdout", "{name: 'bin'}")
+///     .assert()
+///     .stdout(assert_cmd::predicate::yaml_eq(expected));
+/// ```
+///
+/// [Predicate]: https://docs.rs/predicates-core/1.0.0/predicates_core/trait.Predicate.html
+#[cfg(feature = "yaml")]
+pub fn yaml_eq(expected: serde_yaml::Value) -> YamlEqPredicate {
+    YamlEqPredicate {
+        rendered: render_yaml(&expected),
+        expected,
+    }
+}
+
+/// [Predicate] comparing YAML output to an expected value.
+///
+/// Create a `YamlEqPredicate` through [`yaml_eq`].
+///
+/// [Predicate]: https://docs.rs/predicates-core/1.0.0/predicates_core/trait.Predicate.html
+/// [`yaml_eq`]: fn.yaml_eq.html
+#[cfg(feature = "yaml")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct YamlEqPredicate {
+    expected: serde_yaml::Value,
+    rendered: String,
+}
+
+#[cfg(feature = "yaml")]
+impl YamlEqPredicate {
+    /// Describe how `variable` differs from the expected value, if it does.
+    fn compare(&self, variable: &[u8]) -> Result<(), (&'static str, String)> {
+        let actual: serde_yaml::Value = serde_yaml::from_slice(variable)
+            .map_err(|err| ("error", format!("invalid YAML: {}", err)))?;
+        if actual != self.expected {
+            return Err(("diff", unified_diff(&self.rendered, &render_yaml(&actual))));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl predicates_core::reflection::PredicateReflection for YamlEqPredicate {
+    fn parameters<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = predicates_core::reflection::Parameter<'a>> + 'a> {
+        let params = vec![predicates_core::reflection::Parameter::new(
+            "expected",
+            &self.rendered,
+        )];
+        Box::new(params.into_iter())
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl predicates_core::Predicate<[u8]> for YamlEqPredicate {
+    fn eval(&self, variable: &[u8]) -> bool {
+        self.compare(variable).is_ok()
+    }
+
+    fn find_case<'a>(
+        &'a self,
+        expected: bool,
+        variable: &[u8],
+    ) -> Option<predicates_core::reflection::Case<'a>> {
+        structural_case(self, expected, self.compare(variable))
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl fmt::Display for YamlEqPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "var == expected YAML")
+    }
+}
+
+#[cfg(feature = "yaml")]
+fn render_yaml(value: &serde_yaml::Value) -> String {
+    serde_yaml::to_string(value).unwrap_or_else(|err| format!("<unrenderable: {}>", err))
+}
+
+/// The case for a structural comparison, with the parse error or diff as its product.
+#[cfg(any(feature = "toml", feature = "yaml"))]
+fn structural_case<'a>(
+    pred: &'a dyn predicates_core::reflection::PredicateReflection,
+    expected: bool,
+    compared: Result<(), (&'static str, String)>,
+) -> Option<predicates_core::reflection::Case<'a>> {
+    let result = compared.is_ok();
+    if result != expected {
+        return None;
+    }
+    let mut case = predicates_core::reflection::Case::new(Some(pred), result);
+    if let Err((name, description)) = compared {
+        case = case.add_product(predicates_core::reflection::Product::new(name, description));
+    }
+    Some(case)
+}
+
+/// Render the line differences from `expected` to `actual` as a unified diff.
+fn unified_diff(expected: &str, actual: &str) -> String {
+    // Both ending in a newline isn't an extra, empty line.
+    let (expected, actual) = if expected.ends_with('\n') && actual.ends_with('\n') {
+        (&expected[..expected.len() - 1], &actual[..actual.len() - 1])
+    } else {
+        (expected, actual)
+    };
+    let changeset = difference::Changeset::new(expected, actual, "\n");
+    let mut lines = Vec::new();
+    for diff in &changeset.diffs {
+        let (tag, chunk) = match *diff {
+            difference::Difference::Same(ref chunk) => (' ', chunk),
+            difference::Difference::Rem(ref chunk) => ('-', chunk),
+            difference::Difference::Add(ref chunk) => ('+', chunk),
+        };
+        lines.extend(chunk.split('\n').map(|line| (tag, line)));
+    }
+
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (i, &(tag, _)) in lines.iter().enumerate() {
+        if tag == ' ' {
+            continue;
+        }
+        let start = i.saturating_sub(CONTEXT);
+        let end = (i + CONTEXT + 1).min(lines.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut rendered = String::new();
+    for (start, end) in hunks {
+        let count = |range: &[(char, &str)], side: char| {
+            range
+                .iter()
+                .filter(|&&(tag, _)| tag == ' ' || tag == side)
+                .count()
+        };
+        let old_start = count(&lines[..start], '-') + 1;
+        let new_start = count(&lines[..start], '+') + 1;
+        rendered.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_start,
+            count(&lines[start..end], '-'),
+            new_start,
+            count(&lines[start..end], '+')
+        ));
+        for &(tag, line) in &lines[start..end] {
+            rendered.push_str(&format!("{}{}\n", tag, line));
+        }
+    }
+    rendered
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn unified_diff_shows_context() {
+        let expected = "1\n2\n3\n4\n5\n6\n7\n8\n9\n";
+        let actual = "1\n2\n3\n4\nfive\n6\n7\n8\n9\n";
+        assert_eq!(
+            unified_diff(expected, actual),
+            "@@ -2,7 +2,7 @@\n 2\n 3\n 4\n-5\n+five\n 6\n 7\n 8\n"
+        );
+    }
+
+    #[test]
+    fn unified_diff_trailing_newline() {
+        assert_eq!(unified_diff("a\n", "b\n"), "@@ -1,1 +1,1 @@\n-a\n+b\n");
+    }
+
+    #[test]
+    fn unified_diff_splits_distant_changes() {
+        let expected = "a\n1\n2\n3\n4\n5\n6\n7\nb";
+        let actual = "A\n1\n2\n3\n4\n5\n6\n7\nB";
+        assert_eq!(
+            unified_diff(expected, actual),
+            "@@ -1,4 +1,4 @@\n-a\n+A\n 1\n 2\n 3\n@@ -6,4 +6,4 @@\n 5\n 6\n 7\n-b\n+B\n"
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! Structured results of running a command, for storing or sharing.
+//!
+//! Create an [`AssertReport`] through [`Assert::to_report`].  Requires the `serde` feature.
+//!
+//! [`AssertReport`]: struct.AssertReport.html
+//! [`Assert::to_report`]: ../assert/struct.Assert.html#method.to_report
+
+use std::process;
+use std::str;
+
+use serde::Serialize;
+
+/// The outcome of running a command, serializable with [`serde`].
+///
+/// Create an `AssertReport` through [`Assert::to_report`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use assert_cmd::prelude::*;
+///
+/// use std::process::Command;
+///
+/// let report = Command::cargo_bin("bin_fixture")
+///     .unwrap()
+///     .env("stdout", "hello")
+///     .assert()
+///     .to_report();
+/// assert!(report.success);
+/// assert_eq!(report.stdout.text, "hello\n");
+/// ```
+///
+/// [`serde`]: https://docs.rs/serde
+/// [`Assert::to_report`]: ../assert/struct.Assert.html#method.to_report
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AssertReport {
+    /// The context of the run, like the command, in the order added.
+    pub context: Vec<ReportContext>,
+    /// The returned code, or `None` if interrupted.
+    pub code: Option<i32>,
+    /// Whether the command succeeded.
+    pub success: bool,
+    /// What the command wrote to `stdout`.
+    pub stdout: ReportBuffer,
+    /// What the command wrote to `stderr`.
+    pub stderr: ReportBuffer,
+}
+
+impl AssertReport {
+    pub(crate) fn new(context: Vec<ReportContext>, output: &process::Output) -> Self {
+        AssertReport {
+            context,
+            code: output.status.code(),
+            success: output.status.success(),
+            stdout: ReportBuffer::new(&output.stdout),
+            stderr: ReportBuffer::new(&output.stderr),
+        }
+    }
+}
+
+/// A piece of context in an [`AssertReport`].
+///
+/// [`AssertReport`]: struct.AssertReport.html
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReportContext {
+    /// What the context describes, like `command`.
+    pub name: String,
+    /// The rendered context.
+    pub value: String,
+}
+
+/// Output in an [`AssertReport`].
+///
+/// Output is usually text, so it is always available as `text`.  Output that isn't UTF-8 is
+/// decoded lossily into `text`, and its exact `bytes` are kept too.
+///
+/// [`AssertReport`]: struct.AssertReport.html
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReportBuffer {
+    /// The output, decoded lossily if it isn't UTF-8.
+    pub text: String,
+    /// The exact output, only if it isn't UTF-8.
+    pub bytes: Option<Vec<u8>>,
+}
+
+impl ReportBuffer {
+    fn new(buffer: &[u8]) -> Self {
+        match str::from_utf8(buffer) {
+            Ok(text) => ReportBuffer {
+                text: text.to_owned(),
+                bytes: None,
+            },
+            Err(_) => ReportBuffer {
+                text: String::from_utf8_lossy(buffer).into_owned(),
+                bytes: Some(buffer.to_vec()),
+            },
+        }
+    }
+}
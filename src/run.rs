@@ -0,0 +1,1916 @@
+//! Run a [`Command`][Command] under controlled conditions and assert on the result.
+//!
+//! [Command]: https://doc.rust-lang.org/std/process/struct.Command.html
+
+use std::cell;
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi;
+use std::fs;
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+use std::mem;
+use std::path;
+use std::process;
+use std::sync::mpsc;
+use std::thread;
+use std::time;
+
+use predicates_core;
+#[cfg(feature = "tempfile")]
+use tempfile;
+
+use assert::Assert;
+use assert::IntoOutputPredicate;
+use assert::OutputAssertExt;
+use cmd::diff_buffers;
+use cmd::find_bytes;
+use spawn;
+use stdin::CommandStdInExt;
+
+/// Run a [`Command`][Command] under controlled conditions and assert on the result.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use assert_cmd::prelude::*;
+///
+/// use std::process::Command;
+///
+/// Command::cargo_bin("bin_fixture")
+///     .unwrap()
+///     .assert_does_not_leak_env("API_TOKEN")
+///     .success();
+/// ```
+///
+/// [Command]: https://doc.rust-lang.org/std/process/struct.Command.html
+pub trait CommandRunExt {
+    /// Ensure the value of the environment variable `key` is not echoed by the command.
+    ///
+    /// `key` is set to a unique sentinel value before running and both `stdout` and `stderr`
+    /// are scanned for it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .assert_does_not_leak_env("API_TOKEN")
+    ///     .success();
+    /// ```
+    fn assert_does_not_leak_env(self, key: &str) -> Assert;
+
+    /// Send `signal` to the command shortly after starting it, collecting its output.
+    ///
+    /// The command is given [`SIGNAL_DELAY`] to start up, e.g. to install signal handlers,
+    /// before being signaled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// extern crate assert_cmd;
+    /// extern crate libc;
+    ///
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("sh")
+    ///     .arg("-c")
+    ///     .arg("trap 'echo shutting down; exit 0' TERM; sleep 5 > /dev/null & wait")
+    ///     .send_signal_then_assert(libc::SIGTERM)
+    ///     .success()
+    ///     .stdout("shutting down\n");
+    /// ```
+    ///
+    /// [`SIGNAL_DELAY`]: constant.SIGNAL_DELAY.html
+    #[cfg(unix)]
+    fn send_signal_then_assert(self, signal: i32) -> Assert;
+
+    /// Run the command, failing if it doesn't exit within `timeout`.
+    ///
+    /// A command still running after `timeout` is killed.  This overrides any
+    /// [`with_default_timeout`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    /// use std::time::Duration;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .assert_within(Duration::from_secs(30))
+    ///     .success();
+    /// ```
+    ///
+    /// [`with_default_timeout`]: #tymethod.with_default_timeout
+    fn assert_within(self, timeout: time::Duration) -> Assert;
+
+    /// Fail this and any later command run on the current thread that doesn't exit within
+    /// `timeout`.
+    ///
+    /// The default is thread-local: it applies to every later [`OutputAssertExt::assert`] and
+    /// run of `CommandRunExt` on the current thread, whatever the command, but not to other
+    /// threads, like those of other tests.  Runs with their own timeout, like [`assert_within`],
+    /// use it instead.  Use [`set_default_timeout`] to change or clear it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    /// use std::time::Duration;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .with_default_timeout(Duration::from_secs(30))
+    ///     .assert()
+    ///     .success();
+    /// ```
+    ///
+    /// [`OutputAssertExt::assert`]: ../assert/trait.OutputAssertExt.html#tymethod.assert
+    /// [`assert_within`]: #tymethod.assert_within
+    /// [`set_default_timeout`]: fn.set_default_timeout.html
+    fn with_default_timeout(self, timeout: time::Duration) -> Self;
+
+    /// Ensure the command completes on its own within `budget`.
+    ///
+    /// Unlike [`assert_within`], the command is not killed when over `budget`: it is waited on
+    /// to completion, so the full output is available, and then the overrun is reported.  This
+    /// suits performance budgets; to protect against hangs, use [`assert_within`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    /// use std::time::Duration;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .completes_within(Duration::from_secs(1))
+    ///     .success();
+    /// ```
+    ///
+    /// [`assert_within`]: #tymethod.assert_within
+    fn completes_within(self, budget: time::Duration) -> Assert;
+
+    /// Ensure the command exits within `grace` of being sent `signal`, collecting its output.
+    ///
+    /// Like [`send_signal_then_assert`], the command is given [`SIGNAL_DELAY`] to start up.  If it
+    /// is still running after `grace`, it is killed with `SIGKILL` and the assertion fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// extern crate assert_cmd;
+    /// extern crate libc;
+    ///
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    /// use std::time::Duration;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("sleep", "5000")
+    ///     .terminates_within(libc::SIGTERM, Duration::from_secs(1))
+    ///     .interrupted();
+    /// ```
+    ///
+    /// [`send_signal_then_assert`]: #tymethod.send_signal_then_assert
+    /// [`SIGNAL_DELAY`]: constant.SIGNAL_DELAY.html
+    #[cfg(unix)]
+    fn terminates_within(self, signal: i32, grace: time::Duration) -> Assert;
+
+    /// Ensure the command leaves no partial file behind when sent `signal` `after` starting, while
+    /// writing `target`.
+    ///
+    /// The directory containing `target` is compared before and after: any entry created besides
+    /// `target` is reported as a leftover, like the temporary file of a write-then-rename.  Use
+    /// `SIGKILL` to simulate a crash.  Only the caller knows what complete content looks like, so
+    /// check `target`, if present, after the assertion; failures include whether it exists.  A
+    /// relative `target` is resolved against the command's current directory.
+    ///
+    /// A command that exits on its own before `after`, like one that crashes, isn't signaled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// extern crate assert_cmd;
+    /// extern crate libc;
+    ///
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::path::Path;
+    /// use std::process::Command;
+    /// use std::time::Duration;
+    ///
+    /// Command::new("sh")
+    ///     .arg("-c")
+    ///     .arg("echo hello > out.txt.tmp && sleep 5 && mv out.txt.tmp out.txt")
+    ///     .assert_atomic_write(Path::new("out.txt"), libc::SIGKILL, Duration::from_millis(100));
+    /// ```
+    #[cfg(unix)]
+    fn assert_atomic_write(self, target: &path::Path, signal: i32, after: time::Duration)
+        -> Assert;
+
+    /// Ensure the command exits successfully within `grace` of `input` being written to its
+    /// `stdin` and `stdin` closed.
+    ///
+    /// This catches commands that keep waiting for input after EOF.  The command is given `grace`
+    /// to drain `input`, then `grace` to exit, after which it is killed and the assertion fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    /// use std::time::Duration;
+    ///
+    /// Command::new("cat")
+    ///     .stdin_eof_then_assert("hello", Duration::from_secs(1))
+    ///     .stdout("hello");
+    /// ```
+    fn stdin_eof_then_assert<I>(self, input: I, grace: time::Duration) -> Assert
+    where
+        I: Into<Vec<u8>>;
+
+    /// Run the command, also collecting what it writes to each of `fds`, like a status pipe on
+    /// fd 3.
+    ///
+    /// Each fd is connected to a pipe in the command.  Use [`Assert::fd`] or
+    /// [`Assert::get_extra_fd`] to inspect what was written.
+    ///
+    /// Like for [`OutputAssertExt::assert`], a copy with the same program, arguments, environment
+    /// changes, and current directory is run, leaving the command untouched.  Other settings, like
+    /// stdio, aren't copied.
+    ///
+    /// # Panics
+    ///
+    /// If any of `fds` is stdin, stdout, or stderr (`0` to `2`), or negative.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("sh")
+    ///     .arg("-c")
+    ///     .arg("echo ready >&3")
+    ///     .assert_with_fds(&[3])
+    ///     .success()
+    ///     .fd(3, "ready\n");
+    /// ```
+    ///
+    /// [`OutputAssertExt::assert`]: ../assert/trait.OutputAssertExt.html#tymethod.assert
+    /// [`Assert::fd`]: ../assert/struct.Assert.html#method.fd
+    /// [`Assert::get_extra_fd`]: ../assert/struct.Assert.html#method.get_extra_fd
+    #[cfg(unix)]
+    fn assert_with_fds(self, fds: &[i32]) -> Assert;
+
+    /// Run the command with `stdout` and `stderr` interleaved in the order they were written.
+    ///
+    /// Both are written to the same pipe, so the `Assert`'s `stdout` and `stderr` are empty.  Use
+    /// [`Assert::get_combined`] or [`Assert::combined_order`] to inspect the combined output.
+    ///
+    /// Since the command isn't writing to a terminal, its output may be block-buffered: the
+    /// interleaving follows when each stream was flushed, which can differ from what a terminal
+    /// would show.  Commands that flush after each line, or write unbuffered, interleave as
+    /// written.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("sh")
+    ///     .arg("-c")
+    ///     .arg("echo starting >&2; echo result")
+    ///     .assert_combined()
+    ///     .success()
+    ///     .combined_order(&["starting", "result"]);
+    /// ```
+    ///
+    /// [`Assert::get_combined`]: ../assert/struct.Assert.html#method.get_combined
+    /// [`Assert::combined_order`]: ../assert/struct.Assert.html#method.combined_order
+    #[cfg(unix)]
+    fn assert_combined(self) -> Assert;
+
+    /// Run the command, also measuring its peak memory use (max RSS).
+    ///
+    /// Use [`Assert::max_rss`] or [`Assert::max_rss_below`] to inspect it.
+    ///
+    /// On Linux, the kernel reports the peak over the whole life of the process, including before
+    /// it started running the command, when it was still a copy of the test process.  The
+    /// measurement is then at least the test process's own memory use, so it suits generous
+    /// bounds and catching runaway growth rather than exact budgets.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .assert_with_max_rss()
+    ///     .success()
+    ///     .max_rss_below(512 * 1024 * 1024);
+    /// ```
+    ///
+    /// [`Assert::max_rss`]: ../assert/struct.Assert.html#method.max_rss
+    /// [`Assert::max_rss_below`]: ../assert/struct.Assert.html#method.max_rss_below
+    #[cfg(unix)]
+    fn assert_with_max_rss(self) -> Assert;
+
+    /// Run the command in a new process group, tracking it to find leaked children.
+    ///
+    /// Use [`Assert::no_orphans`] to ensure nothing is left running.  Children still holding
+    /// `stdout` or `stderr` open delay collecting the output until they exit.
+    ///
+    /// Like for [`assert_with_fds`], a copy of the command is run, leaving it untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .assert_with_process_group()
+    ///     .success()
+    ///     .no_orphans();
+    /// ```
+    ///
+    /// [`Assert::no_orphans`]: ../assert/struct.Assert.html#method.no_orphans
+    /// [`assert_with_fds`]: #tymethod.assert_with_fds
+    #[cfg(unix)]
+    fn assert_with_process_group(self) -> Assert;
+
+    /// Ensure the command behaves the same when run under each of `locales`.
+    ///
+    /// The command is run once per locale with `LC_ALL` set, comparing the code, `stdout`, and
+    /// `stderr` of each run against the first.  The `Assert` for the first locale is returned.
+    ///
+    /// `LC_ALL` is restored on the command afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "1.5")
+    ///     .assert_locale_stable(&["C", "de_DE.UTF-8", "fr_FR.UTF-8"])
+    ///     .success();
+    /// ```
+    fn assert_locale_stable(self, locales: &[&str]) -> Assert;
+
+    /// Ensure the size of `stdout` is the same over `runs` runs of the command.
+    ///
+    /// This checks tools with varying but fixed-width output, like timestamps, are deterministic
+    /// in size.  On failure, the size of each run is listed.  The `Assert` for the first run is
+    /// returned.
+    ///
+    /// # Panics
+    ///
+    /// If `runs` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .assert_stable_size(3)
+    ///     .success();
+    /// ```
+    fn assert_stable_size(self, runs: usize) -> Assert;
+
+    /// Ensure the command writes identical `stdout` over two runs with `SOURCE_DATE_EPOCH` set to
+    /// `epoch`, and different `stdout` with another epoch.
+    ///
+    /// This checks the [reproducible builds] contract, that timestamps come from
+    /// `SOURCE_DATE_EPOCH` rather than the clock.  The other epoch is over a year away from `epoch`,
+    /// so even output showing only the year changes.  On failure, the differences between the
+    /// runs are shown.  `SOURCE_DATE_EPOCH` is restored on the command afterwards.  The `Assert`
+    /// for the first run is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("sh")
+    ///     .arg("-c")
+    ///     .arg("echo built at $SOURCE_DATE_EPOCH")
+    ///     .assert_reproducible_with_epoch(1_700_000_000)
+    ///     .success();
+    /// ```
+    ///
+    /// [reproducible builds]: https://reproducible-builds.org/specs/source-date-epoch/
+    fn assert_reproducible_with_epoch(self, epoch: u64) -> Assert;
+
+    /// Ensure the command writes the same `stdout` as `reference`, e.g. a tool being replaced.
+    ///
+    /// Both commands are run and the differences, if any, are shown from `reference` to the
+    /// command.  To feed both the same stdin, see [`StdInCommand::assert_matches_command`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .assert_matches_command(Command::new("echo").arg("hello"))
+    ///     .success();
+    /// ```
+    ///
+    /// [`StdInCommand::assert_matches_command`]: ../stdin/struct.StdInCommand.html#method.assert_matches_command
+    fn assert_matches_command(self, reference: &mut process::Command) -> Assert;
+
+    /// Ensure the command writes the same `stdout` and returns the same code as `reference`.
+    ///
+    /// See [`assert_matches_command`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .assert_matches_command_exactly(Command::new("echo").arg("hello"));
+    /// ```
+    ///
+    /// [`assert_matches_command`]: #tymethod.assert_matches_command
+    fn assert_matches_command_exactly(self, reference: &mut process::Command) -> Assert;
+
+    /// Ensure the command writes the same `stdout` and returns the same code as the shell command
+    /// `shell_cmd`.
+    ///
+    /// `shell_cmd` is run with `sh -c`, given the command's arguments as `"$@"`, along with its
+    /// environment changes and current directory.  This validates a reimplementation against the
+    /// tool, or shell builtin, it replaces.  See [`assert_matches_command_exactly`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("/bin/echo")
+    ///     .arg("hello")
+    ///     .assert_matches_shell("echo \"$@\"");
+    /// ```
+    ///
+    /// [`assert_matches_command_exactly`]: #tymethod.assert_matches_command_exactly
+    fn assert_matches_shell(self, shell_cmd: &str) -> Assert;
+
+    /// Ensure the command writes less than `baseline`, e.g. checking a `--quiet` flag.
+    ///
+    /// Both commands are run and the command's `stdout` and `stderr` must total strictly fewer
+    /// bytes than `baseline`'s.  On failure, both totals are shown.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .assert_quieter_than(
+    ///         Command::cargo_bin("bin_fixture")
+    ///             .unwrap()
+    ///             .env("stdout", "hello")
+    ///             .env("stderr", "progress"),
+    ///     )
+    ///     .success();
+    /// ```
+    fn assert_quieter_than(self, baseline: &mut process::Command) -> Assert;
+
+    /// Ensure the command follows the convention of `-` meaning `stdout` for `flag`.
+    ///
+    /// `flag` and `-` are appended to the arguments, e.g. `-o -`.  The command must write to
+    /// `stdout` what `pred` expects, rather than to a file named `-` in its current directory.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("convert")
+    ///     .unwrap()
+    ///     .arg("input.txt")
+    ///     .assert_dash_is_stdout("--output", "converted\n")
+    ///     .success();
+    /// ```
+    fn assert_dash_is_stdout<I, P>(self, flag: &str, pred: I) -> Assert
+    where
+        I: IntoOutputPredicate<P>,
+        P: predicates_core::Predicate<[u8]>;
+
+    /// Ensure the command, a formatter, leaves its own output unchanged.
+    ///
+    /// `input` is given on stdin, and the formatted `stdout` is then given to a second run.  Both
+    /// runs must succeed, and the second must write the same `stdout` as the first.  On failure,
+    /// the differences from the first pass to the second are shown.  The `Assert` for the first
+    /// pass is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("rustfmt")
+    ///     .arg("--emit=stdout")
+    ///     .assert_format_idempotent(b"fn main(){}")
+    ///     .stdout("fn main() {}\n");
+    /// ```
+    fn assert_format_idempotent(self, input: &[u8]) -> Assert;
+
+    /// Ensure the command follows the `--help` convention.
+    ///
+    /// `--help` is appended to the arguments and the command must succeed, writing something to
+    /// `stdout`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "Usage: bin_fixture [OPTIONS]")
+    ///     .assert_help();
+    /// ```
+    fn assert_help(self) -> Assert;
+
+    /// Ensure the command follows the `--version` convention.
+    ///
+    /// `--version` is appended to the arguments and the command must succeed, writing a version
+    /// number, like `1.2` or `1.2.3`, to `stdout` and including `expected`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "bin_fixture 1.2.3")
+    ///     .assert_version("1.2.3");
+    /// ```
+    fn assert_version(self, expected: &str) -> Assert;
+
+    /// Drive the command interactively, reacting to each line it writes to `stdout`.
+    ///
+    /// `on_line` is called with each line, without its line ending, and returns what to do next,
+    /// like answering a prompt.  Once the command closes `stdout`, usually by exiting, an
+    /// `Assert` for the whole run is returned.  The command's stdin stays open until
+    /// [`LineAction::CloseStdin`] is returned or `stdout` is closed, so a command waiting for
+    /// input must be sent a reply or have stdin closed.
+    ///
+    /// Only complete lines are seen, so prompts without a line ending aren't reacted to until
+    /// later output.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    /// use assert_cmd::run::LineAction;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("sh")
+    ///     .arg("-c")
+    ///     .arg("echo 'name?'; read name; echo \"hello $name\"")
+    ///     .for_each_stdout_line(|line| match line {
+    ///         "name?" => LineAction::WriteStdin("world\n".to_owned()),
+    ///         _ => LineAction::Continue,
+    ///     })
+    ///     .stdout("name?\nhello world\n");
+    /// ```
+    ///
+    /// [`LineAction::CloseStdin`]: enum.LineAction.html#variant.CloseStdin
+    fn for_each_stdout_line<F>(self, on_line: F) -> Assert
+    where
+        F: FnMut(&str) -> LineAction;
+
+    /// Ensure `copies` of the command can run concurrently without interfering with each other.
+    ///
+    /// The copies are started together, each must succeed, and each must return the same code,
+    /// `stdout`, and `stderr` as the first.  On failure, the differing copies are listed.  The
+    /// `Assert` of each copy is returned, in order.
+    ///
+    /// Each copy is created from the command's program, arguments, environment changes, and
+    /// current directory.  Other configuration, like `env_clear` or stdio, is not copied.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// let asserts = Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .env("stdout", "hello")
+    ///     .assert_concurrent(4);
+    /// assert_eq!(asserts.len(), 4);
+    /// ```
+    fn assert_concurrent(self, copies: usize) -> Vec<Assert>;
+
+    /// Ensure the command did not write outside of `allowed`.
+    ///
+    /// This is a heuristic: the entries directly within the command's current directory and
+    /// `$HOME` are compared before and after running, reporting any created, modified, or removed
+    /// outside of `allowed`.  Writes deeper in those directories are caught only when they
+    /// change the modified time of a direct entry, e.g. by creating a file in a subdirectory.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::env;
+    /// use std::process::Command;
+    ///
+    /// let allowed = env::temp_dir();
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .current_dir(&allowed)
+    ///     .assert_no_writes_outside(&allowed)
+    ///     .success();
+    /// ```
+    fn assert_no_writes_outside(self, allowed: &path::Path) -> Assert;
+
+    /// Ensure the command, e.g. with `--dry-run`, made no changes within `watched`.
+    ///
+    /// The files and directories within `watched` are scanned recursively before and after
+    /// running, reporting any added, removed, or modified.  Files are compared by size and
+    /// modified time.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::env;
+    /// use std::process::Command;
+    ///
+    /// let watched = env::temp_dir();
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .arg("--dry-run")
+    ///     .current_dir(&watched)
+    ///     .assert_dry_run_clean(&watched)
+    ///     .success();
+    /// ```
+    fn assert_dry_run_clean(self, watched: &path::Path) -> Assert;
+
+    /// Disable colored output by the command, following the `NO_COLOR` and `CLICOLOR_FORCE`
+    /// conventions.
+    ///
+    /// `NO_COLOR` is set and `CLICOLOR_FORCE` is removed.  Failures include `color=never`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .no_color()
+    ///     .assert()
+    ///     .success();
+    /// ```
+    fn no_color(self) -> Self;
+
+    /// Force colored output by the command, even when not writing to a terminal, following the
+    /// `NO_COLOR` and `CLICOLOR_FORCE` conventions.
+    ///
+    /// `CLICOLOR_FORCE` is set and `NO_COLOR` is removed.  Failures include `color=always`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .force_color()
+    ///     .assert()
+    ///     .success();
+    /// ```
+    fn force_color(self) -> Self;
+
+    /// Append `--` and then `args`, so they are treated as positional even if they look like
+    /// flags.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .args_after_double_dash(&["-x", "--verbose"])
+    ///     .assert()
+    ///     .success();
+    /// ```
+    fn args_after_double_dash(self, args: &[&str]) -> Self;
+
+    /// Ensure the command treats `args` after `--` as positional, for commands that echo their
+    /// positional arguments.
+    ///
+    /// `--` and then `args` are appended, the command must succeed, and each of `args` must
+    /// appear verbatim in `stdout`, confirming it wasn't parsed as a flag.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("echo")
+    ///     .assert_args_after_double_dash(&["-x", "--verbose"]);
+    /// ```
+    fn assert_args_after_double_dash(self, args: &[&str]) -> Assert;
+
+    /// Set `PATH` to exactly `dirs`, so the command finds only the tools provided, e.g. stubs.
+    ///
+    /// Failures include the effective `PATH`.
+    ///
+    /// # Panics
+    ///
+    /// If a directory contains the platform's `PATH` separator.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::path::Path;
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .with_path(&[Path::new("tests/stubs")])
+    ///     .assert()
+    ///     .success();
+    /// ```
+    fn with_path(self, dirs: &[&path::Path]) -> Self;
+
+    /// Put `dirs` before the existing `PATH`, so the command prefers the tools provided.
+    ///
+    /// See [`with_path`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::path::Path;
+    /// use std::process::Command;
+    ///
+    /// Command::cargo_bin("bin_fixture")
+    ///     .unwrap()
+    ///     .prepend_path(&[Path::new("tests/stubs")])
+    ///     .assert()
+    ///     .success();
+    /// ```
+    ///
+    /// [`with_path`]: #tymethod.with_path
+    fn prepend_path(self, dirs: &[&path::Path]) -> Self;
+
+    /// Write `contents` to a temporary file and pass it to the command with `--config <path>`.
+    ///
+    /// The file is deleted when the returned [`TempPath`] is dropped.  Its path is added to the
+    /// context of failures.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// let mut cmd = Command::cargo_bin("bin_fixture").unwrap();
+    /// let (cmd, config) = cmd.with_config_file("verbose = true").unwrap();
+    /// cmd.assert().success();
+    /// println!("{}", config.display());
+    /// ```
+    ///
+    /// [`TempPath`]: https://docs.rs/tempfile/3/tempfile/struct.TempPath.html
+    #[cfg(feature = "tempfile")]
+    fn with_config_file(self, contents: &str) -> io::Result<(Self, tempfile::TempPath)>
+    where
+        Self: Sized,
+    {
+        self.with_config_file_arg("--config", contents)
+    }
+
+    /// Write `contents` to a temporary file and pass it to the command with `<arg> <path>`.
+    ///
+    /// The file is deleted when the returned [`TempPath`] is dropped.  Its path is added to the
+    /// context of failures.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// let mut cmd = Command::cargo_bin("bin_fixture").unwrap();
+    /// let (cmd, _config) = cmd.with_config_file_arg("-c", "verbose = true").unwrap();
+    /// cmd.assert().success();
+    /// ```
+    ///
+    /// [`TempPath`]: https://docs.rs/tempfile/3/tempfile/struct.TempPath.html
+    #[cfg(feature = "tempfile")]
+    fn with_config_file_arg(
+        self,
+        arg: &str,
+        contents: &str,
+    ) -> io::Result<(Self, tempfile::TempPath)>
+    where
+        Self: Sized;
+}
+
+/// What [`CommandRunExt::for_each_stdout_line`] should do after a line of `stdout`.
+///
+/// [`CommandRunExt::for_each_stdout_line`]: trait.CommandRunExt.html#tymethod.for_each_stdout_line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineAction {
+    /// Keep reading.
+    Continue,
+    /// Write the text, as is, to the command's stdin.
+    WriteStdin(String),
+    /// Fail unless the next line is exactly the text.
+    Expect(String),
+    /// Close the command's stdin, signaling the end of input.
+    CloseStdin,
+}
+
+impl CommandRunExt for &mut process::Command {
+    fn assert_does_not_leak_env(self, key: &str) -> Assert {
+        let sentinel = sentinel();
+        self.env(key, &sentinel);
+        let assert = self.assert();
+
+        let leaks: Vec<_> = {
+            let output = assert.get_output();
+            vec![("stdout", &output.stdout), ("stderr", &output.stderr)]
+                .into_iter()
+                .filter_map(|(stream, buffer)| {
+                    find_bytes(buffer, sentinel.as_bytes())
+                        .map(|offset| format!("{} at offset {}", stream, offset))
+                })
+                .collect()
+        };
+        if !leaks.is_empty() {
+            panic!(
+                "Leaked `{}` ({}) to {}\n{}",
+                key,
+                sentinel,
+                leaks.join(", "),
+                assert
+            );
+        }
+        assert
+    }
+
+    #[cfg(unix)]
+    fn send_signal_then_assert(self, signal: i32) -> Assert {
+        let mut running = spawn::spawn(self).unwrap();
+        thread::sleep(SIGNAL_DELAY);
+        running.signal(signal).unwrap();
+        let killed = kill_after(&mut running, default_timeout());
+        let (output, timings) = running.wait().unwrap();
+        let assert = Assert::new(output)
+            .set_timings(timings)
+            .append_command(self)
+            .append_context("signal", signal);
+        check_timeout(assert, killed)
+    }
+
+    fn assert_within(self, timeout: time::Duration) -> Assert {
+        assert_within(self, timeout)
+    }
+
+    fn with_default_timeout(self, timeout: time::Duration) -> Self {
+        set_default_timeout(Some(timeout));
+        self
+    }
+
+    fn completes_within(self, budget: time::Duration) -> Assert {
+        let mut running = spawn::spawn(self).unwrap();
+        let killed = kill_after(&mut running, default_timeout());
+        let (output, timings) = running.wait().unwrap();
+        let assert = Assert::new(output)
+            .set_timings(timings)
+            .append_command(self)
+            .append_context("budget", format!("{:?}", budget));
+        let assert = check_timeout(assert, killed);
+        let total = assert.total();
+        if budget < total {
+            panic!(
+                "Command took {:?}, over its budget of {:?}\n{}",
+                total, budget, assert
+            );
+        }
+        assert
+    }
+
+    #[cfg(unix)]
+    fn terminates_within(self, signal: i32, grace: time::Duration) -> Assert {
+        let running = spawn::spawn(self).unwrap();
+        thread::sleep(SIGNAL_DELAY);
+        running.signal(signal).unwrap();
+        let (assert, exited) = wait_or_kill(running, self, grace);
+        let assert = assert.append_context("signal", signal);
+        if !exited {
+            panic!(
+                "Command still running {:?} after signal, killed\n{}",
+                grace, assert
+            );
+        }
+        assert
+    }
+
+    #[cfg(unix)]
+    fn assert_atomic_write(
+        self,
+        target: &path::Path,
+        signal: i32,
+        after: time::Duration,
+    ) -> Assert {
+        let target = match self.get_current_dir() {
+            Some(dir) => dir.join(target),
+            None => env::current_dir().unwrap().join(target),
+        };
+        let dirs: Vec<_> = target
+            .parent()
+            .map(path::Path::to_owned)
+            .into_iter()
+            .collect();
+
+        let before = scan_entries(&dirs, &target);
+        let mut running = spawn::spawn(self).unwrap();
+        if running.wait_timeout(after).unwrap().is_none() {
+            running.signal(signal).unwrap();
+        }
+        let killed = kill_after(&mut running, default_timeout());
+        let (output, timings) = running.wait().unwrap();
+        let remaining = scan_entries(&dirs, &target);
+
+        let assert = Assert::new(output)
+            .set_timings(timings)
+            .append_command(self)
+            .append_context("signal", signal)
+            .append_context(
+                "target",
+                format!(
+                    "{} ({})",
+                    target.display(),
+                    if target.exists() {
+                        "present"
+                    } else {
+                        "missing"
+                    }
+                ),
+            );
+        let assert = check_timeout(assert, killed);
+        let leftovers: Vec<_> = remaining
+            .keys()
+            .filter(|entry| !before.contains_key(*entry))
+            .map(|entry| format!("leftover {}", entry.display()))
+            .collect();
+        if !leftovers.is_empty() {
+            panic!(
+                "Unexpected partial write after signal\n{}\n{}",
+                leftovers.join("\n"),
+                assert
+            );
+        }
+        assert
+    }
+
+    fn stdin_eof_then_assert<I>(self, input: I, grace: time::Duration) -> Assert
+    where
+        I: Into<Vec<u8>>,
+    {
+        let input = input.into();
+        let (running, mut stdin) = spawn::spawn_with_stdin(self).unwrap();
+        let (written, drained) = mpsc::channel();
+        thread::spawn(move || {
+            // A command exiting without reading everything is left to the assertions.
+            let _ = stdin.write_all(&input);
+            drop(stdin);
+            let _ = written.send(());
+        });
+        let drained = drained.recv_timeout(grace).is_ok();
+        let (assert, exited) = wait_or_kill(running, self, grace);
+        if !exited {
+            panic!(
+                "Command still running {:?} after stdin {}, killed\n{}",
+                grace,
+                if drained { "closed" } else { "written" },
+                assert
+            );
+        }
+        assert.success()
+    }
+
+    #[cfg(unix)]
+    fn assert_with_fds(self, fds: &[i32]) -> Assert {
+        if let Some(fd) = fds.iter().find(|&&fd| fd <= 2) {
+            panic!(
+                "Invalid fd {}, only fds after stderr (2) can be collected",
+                fd
+            );
+        }
+        let mut running = spawn::spawn_with_fds(spawn::copy(self), fds).unwrap();
+        let killed = kill_after(&mut running, default_timeout());
+        let (output, timings, extra_fds) = running.wait_with_fds().unwrap();
+        let assert = Assert::new(output)
+            .set_timings(timings)
+            .set_extra_fds(extra_fds)
+            .append_command(self);
+        check_timeout(assert, killed)
+    }
+
+    #[cfg(unix)]
+    fn assert_combined(self) -> Assert {
+        let mut running = spawn::spawn_combined(self).unwrap();
+        let killed = kill_after(&mut running, default_timeout());
+        let (mut output, timings) = running.wait().unwrap();
+        let combined = mem::take(&mut output.stdout);
+        let assert = Assert::new(output)
+            .set_timings(timings)
+            .set_combined(combined)
+            .append_command(self);
+        check_timeout(assert, killed)
+    }
+
+    #[cfg(unix)]
+    fn assert_with_max_rss(self) -> Assert {
+        let mut running = spawn::spawn(self).unwrap();
+        let killed = kill_after(&mut running, default_timeout());
+        let (output, timings, max_rss) = running.wait_with_max_rss().unwrap();
+        let assert = Assert::new(output)
+            .set_timings(timings)
+            .set_max_rss(max_rss)
+            .append_command(self)
+            .append_context("max_rss", max_rss);
+        check_timeout(assert, killed)
+    }
+
+    #[cfg(unix)]
+    fn assert_with_process_group(self) -> Assert {
+        let mut running = spawn::spawn_in_process_group(spawn::copy(self)).unwrap();
+        let pgid = running.id() as i32;
+        let killed = kill_after(&mut running, default_timeout());
+        let (output, timings) = running.wait().unwrap();
+        let assert = Assert::new(output)
+            .set_timings(timings)
+            .set_process_group(pgid)
+            .append_command(self)
+            .append_context("process_group", pgid);
+        check_timeout(assert, killed)
+    }
+
+    fn assert_locale_stable(self, locales: &[&str]) -> Assert {
+        let (baseline, rest) = locales
+            .split_first()
+            .expect("At least one locale is required");
+        let previous = get_env(self, "LC_ALL");
+        self.env("LC_ALL", baseline);
+        let expected = self.assert().append_context("locale", baseline.to_string());
+
+        let mut failures = Vec::new();
+        for locale in rest {
+            self.env("LC_ALL", locale);
+            let actual = self.assert().into_output();
+            failures.extend(
+                compare_output(expected.get_output(), &actual)
+                    .into_iter()
+                    .map(|failure| format!("LC_ALL={}: {}", locale, failure)),
+            );
+        }
+        restore_env(self, "LC_ALL", previous);
+        if !failures.is_empty() {
+            panic!(
+                "Unexpected locale dependence, compared to LC_ALL={}\n{}\n{}",
+                baseline,
+                failures.join("\n"),
+                expected
+            );
+        }
+        expected
+    }
+
+    fn assert_stable_size(self, runs: usize) -> Assert {
+        assert!(runs != 0, "At least one run is required");
+        let first = self.assert().append_context("runs", runs);
+        let mut sizes = vec![first.get_output().stdout.len()];
+        for _ in 1..runs {
+            sizes.push(self.assert().get_output().stdout.len());
+        }
+        if sizes.iter().any(|&size| size != sizes[0]) {
+            let listing: Vec<_> = sizes
+                .iter()
+                .enumerate()
+                .map(|(i, size)| format!("run {}: {} bytes", i, size))
+                .collect();
+            panic!(
+                "Unstable stdout size over {} runs\n{}\n{}",
+                runs,
+                listing.join("\n"),
+                first
+            );
+        }
+        first
+    }
+
+    fn assert_reproducible_with_epoch(self, epoch: u64) -> Assert {
+        let previous = get_env(self, "SOURCE_DATE_EPOCH");
+        let other = if EPOCH_OFFSET <= epoch {
+            epoch - EPOCH_OFFSET
+        } else {
+            epoch + EPOCH_OFFSET
+        };
+
+        self.env("SOURCE_DATE_EPOCH", epoch.to_string());
+        let first = self.assert().append_context("SOURCE_DATE_EPOCH", epoch);
+        let second = self.assert().into_output();
+        self.env("SOURCE_DATE_EPOCH", other.to_string());
+        let changed = self.assert().into_output();
+        restore_env(self, "SOURCE_DATE_EPOCH", previous);
+
+        if first.get_output().stdout != second.stdout {
+            panic!(
+                "Unreproducible stdout, from first to second run:\n{}\n{}",
+                diff_buffers(&first.get_output().stdout, &second.stdout),
+                first
+            );
+        }
+        if first.get_output().stdout == changed.stdout {
+            panic!(
+                "Unexpected stdout, unchanged with SOURCE_DATE_EPOCH={}\n{}",
+                other, first
+            );
+        }
+        first
+    }
+
+    fn assert_matches_command(self, reference: &mut process::Command) -> Assert {
+        let expected = reference.assert().into_output();
+        matches_reference(self.assert(), reference, &expected, false)
+    }
+
+    fn assert_matches_command_exactly(self, reference: &mut process::Command) -> Assert {
+        let expected = reference.assert().into_output();
+        matches_reference(self.assert(), reference, &expected, true)
+    }
+
+    fn assert_matches_shell(self, shell_cmd: &str) -> Assert {
+        let mut reference = shell_reference(self, shell_cmd);
+        self.assert_matches_command_exactly(&mut reference)
+    }
+
+    fn assert_quieter_than(self, baseline: &mut process::Command) -> Assert {
+        let expected = baseline.assert().into_output();
+        let assert = self
+            .assert()
+            .append_context("baseline", format!("{:?}", baseline));
+        let baseline_bytes = expected.stdout.len() + expected.stderr.len();
+        let bytes = {
+            let output = assert.get_output();
+            output.stdout.len() + output.stderr.len()
+        };
+        if bytes >= baseline_bytes {
+            panic!(
+                "Unexpected output size, {} bytes in total, expected fewer than the baseline's {} bytes\n{}",
+                bytes, baseline_bytes, assert
+            );
+        }
+        assert
+    }
+
+    fn assert_dash_is_stdout<I, P>(self, flag: &str, pred: I) -> Assert
+    where
+        I: IntoOutputPredicate<P>,
+        P: predicates_core::Predicate<[u8]>,
+    {
+        let dash = match self.get_current_dir() {
+            Some(dir) => dir.join("-"),
+            None => env::current_dir().unwrap().join("-"),
+        };
+        let stamp = |path: &path::Path| {
+            fs::metadata(path)
+                .ok()
+                .map(|metadata| (metadata.len(), metadata.modified().ok()))
+        };
+        let before = stamp(&dash);
+        let assert = self
+            .args([flag, "-"])
+            .assert()
+            .append_context("flag", format!("{} -", flag));
+        let after = stamp(&dash);
+        if after.is_some() && after != before {
+            panic!(
+                "Unexpected file {}, expected `-` to mean stdout\n{}",
+                dash.display(),
+                assert
+            );
+        }
+        assert.stdout(pred)
+    }
+
+    fn assert_format_idempotent(self, input: &[u8]) -> Assert {
+        let first = self
+            .with_stdin()
+            .buffer(input.to_vec())
+            .assert()
+            .append_context("pass", 1)
+            .success();
+        let formatted = first.get_output().stdout.clone();
+        let second = self
+            .with_stdin()
+            .buffer(formatted)
+            .assert()
+            .append_context("pass", 2)
+            .success();
+        if first.get_output().stdout != second.get_output().stdout {
+            panic!(
+                "Unexpected reformatting, from first to second pass:\n{}\n{}",
+                diff_buffers(&first.get_output().stdout, &second.get_output().stdout),
+                first
+            );
+        }
+        first
+    }
+
+    fn assert_help(self) -> Assert {
+        self.arg("--help");
+        let assert = self.assert().append_context("flag", "--help").success();
+        if assert
+            .get_output()
+            .stdout
+            .iter()
+            .all(|b| b.is_ascii_whitespace())
+        {
+            panic!("Unexpected stdout, `--help` printed nothing\n{}", assert);
+        }
+        assert
+    }
+
+    fn assert_version(self, expected: &str) -> Assert {
+        self.arg("--version");
+        let assert = self.assert().append_context("flag", "--version").success();
+        let stdout = String::from_utf8_lossy(&assert.get_output().stdout).into_owned();
+        if !has_version(&stdout) {
+            panic!("Unexpected stdout, no version number\n{}", assert);
+        }
+        if !stdout.contains(expected) {
+            panic!(
+                "Unexpected stdout, missing version `{}`\n{}",
+                expected, assert
+            );
+        }
+        assert
+    }
+
+    fn for_each_stdout_line<F>(self, mut on_line: F) -> Assert
+    where
+        F: FnMut(&str) -> LineAction,
+    {
+        let (mut running, stdin, stdout) = spawn::spawn_interactive(self).unwrap();
+        let deadline = default_timeout().map(|timeout| (time::Instant::now() + timeout, timeout));
+        // Read on another thread, so a command that stops writing can still time out.
+        let (sender, lines) = mpsc::channel();
+        let reader = thread::spawn(move || -> io::Result<()> {
+            let mut stdout = io::BufReader::new(stdout);
+            loop {
+                let mut line = Vec::new();
+                if stdout.read_until(b'\n', &mut line)? == 0 {
+                    return Ok(());
+                }
+                if sender.send((line, time::Instant::now())).is_err() {
+                    return Ok(());
+                }
+            }
+        });
+        let mut stdin = Some(stdin);
+        let mut transcript = Vec::new();
+        let mut first_stdout = None;
+        let mut stdout_lines = Vec::new();
+        let mut expected: Option<String> = None;
+        let mut failure = None;
+        let mut killed = None;
+        loop {
+            let received = match deadline {
+                Some((deadline, timeout)) => {
+                    let remaining = deadline
+                        .checked_duration_since(time::Instant::now())
+                        .unwrap_or_default();
+                    match lines.recv_timeout(remaining) {
+                        Ok(received) => Some(received),
+                        Err(mpsc::RecvTimeoutError::Timeout) => {
+                            killed = Some(timeout);
+                            break;
+                        }
+                        Err(mpsc::RecvTimeoutError::Disconnected) => None,
+                    }
+                }
+                None => lines.recv().ok(),
+            };
+            let (line, now) = match received {
+                Some(received) => received,
+                None => break,
+            };
+            let start = transcript.len();
+            transcript.extend_from_slice(&line);
+            first_stdout.get_or_insert(now);
+            if transcript.ends_with(b"\n") {
+                stdout_lines.push(now);
+            }
+            let line = String::from_utf8_lossy(&transcript[start..]);
+            let line = line.trim_end_matches('\n').trim_end_matches('\r');
+            if let Some(expected) = expected.take() {
+                if line != expected {
+                    failure = Some(format!("Expected line `{}`, got `{}`", expected, line));
+                    break;
+                }
+            }
+            match on_line(line) {
+                LineAction::Continue => {}
+                LineAction::WriteStdin(input) => {
+                    let written = match stdin {
+                        Some(ref mut stdin) => stdin.write_all(input.as_bytes()),
+                        None => Err(io::Error::new(io::ErrorKind::BrokenPipe, "stdin closed")),
+                    };
+                    if let Err(err) = written {
+                        failure = Some(format!("Failed to write {:?} to stdin: {}", input, err));
+                        break;
+                    }
+                }
+                LineAction::Expect(line) => expected = Some(line),
+                LineAction::CloseStdin => stdin = None,
+            }
+        }
+        drop(stdin);
+        if failure.is_some() || killed.is_some() {
+            // Already exited is fine.
+            let _ = running.kill();
+        } else if let Some(expected) = expected {
+            failure = Some(format!("Expected line `{}`, got end of output", expected));
+        } else {
+            killed = kill_after(&mut running, deadline.map(|(_, timeout)| timeout));
+        }
+        for (line, _) in lines {
+            transcript.extend_from_slice(&line);
+        }
+        reader.join().expect("Couldn't join stdout reader").unwrap();
+
+        let (mut output, mut timings) = running.wait().unwrap();
+        output.stdout = transcript;
+        timings.first_stdout = first_stdout;
+        timings.stdout_lines = stdout_lines;
+        let assert = Assert::new(output)
+            .set_timings(timings)
+            .append_command(self);
+        let assert = check_timeout(assert, killed);
+        if let Some(failure) = failure {
+            panic!("{}\n{}", failure, assert);
+        }
+        assert
+    }
+
+    fn assert_concurrent(self, copies: usize) -> Vec<Assert> {
+        // The copies run on other threads, with the default of this one.
+        let timeout = default_timeout();
+        let handles: Vec<_> = (0..copies)
+            .map(|_| {
+                let copy = spawn::copy(self);
+                thread::spawn(move || {
+                    let mut running = spawn::spawn(&copy)?;
+                    let killed = kill_after(&mut running, timeout);
+                    running
+                        .wait()
+                        .map(|(output, timings)| (output, timings, killed))
+                })
+            })
+            .collect();
+        let asserts: Vec<_> = handles
+            .into_iter()
+            .enumerate()
+            .map(|(i, handle)| {
+                let (output, timings, killed) = handle.join().unwrap().unwrap();
+                let assert = Assert::new(output)
+                    .set_timings(timings)
+                    .append_command(self)
+                    .append_context("copy", i);
+                check_timeout(assert, killed)
+            })
+            .collect();
+
+        let mut failed = None;
+        let mut failures = Vec::new();
+        for (i, assert) in asserts.iter().enumerate() {
+            let output = assert.get_output();
+            let mut differences = Vec::new();
+            if !output.status.success() {
+                differences.push(format!("failed with {:?}", output.status.code()));
+            }
+            if i != 0 {
+                differences.extend(compare_output(asserts[0].get_output(), output));
+            }
+            if !differences.is_empty() {
+                failed.get_or_insert(i);
+                failures.extend(
+                    differences
+                        .into_iter()
+                        .map(|difference| format!("copy {}: {}", i, difference)),
+                );
+            }
+        }
+        if let Some(i) = failed {
+            panic!(
+                "Unexpected interference between {} concurrent copies\n{}\n{}",
+                copies,
+                failures.join("\n"),
+                asserts[i]
+            );
+        }
+        asserts
+    }
+
+    fn assert_no_writes_outside(self, allowed: &path::Path) -> Assert {
+        let allowed = fs::canonicalize(allowed).unwrap_or_else(|_| allowed.to_owned());
+        let mut dirs = vec![match self.get_current_dir() {
+            Some(dir) => dir.to_owned(),
+            None => env::current_dir().unwrap(),
+        }];
+        dirs.extend(env::var_os("HOME").map(path::PathBuf::from));
+        let dirs: Vec<_> = dirs
+            .into_iter()
+            .filter_map(|dir| fs::canonicalize(dir).ok())
+            .filter(|dir| !dir.starts_with(&allowed))
+            .collect();
+
+        let before = scan_entries(&dirs, &allowed);
+        let assert = self
+            .assert()
+            .append_context("allowed", allowed.display().to_string());
+        let after = scan_entries(&dirs, &allowed);
+
+        let writes = diff_entries(&before, &after, "created");
+        if !writes.is_empty() {
+            panic!(
+                "Unexpected writes outside {}\n{}\n{}",
+                allowed.display(),
+                writes.join("\n"),
+                assert
+            );
+        }
+        assert
+    }
+
+    fn assert_dry_run_clean(self, watched: &path::Path) -> Assert {
+        let before = scan_tree(watched);
+        let assert = self
+            .assert()
+            .append_context("watched", watched.display().to_string());
+        let after = scan_tree(watched);
+
+        let changes = diff_entries(&before, &after, "added");
+        if !changes.is_empty() {
+            panic!(
+                "Unexpected changes within {}\n{}\n{}",
+                watched.display(),
+                changes.join("\n"),
+                assert
+            );
+        }
+        assert
+    }
+
+    fn no_color(self) -> Self {
+        self.env("NO_COLOR", "1").env_remove("CLICOLOR_FORCE")
+    }
+
+    fn force_color(self) -> Self {
+        self.env("CLICOLOR_FORCE", "1").env_remove("NO_COLOR")
+    }
+
+    fn args_after_double_dash(self, args: &[&str]) -> Self {
+        self.arg("--").args(args)
+    }
+
+    fn assert_args_after_double_dash(self, args: &[&str]) -> Assert {
+        let assert = self.args_after_double_dash(args).assert().success();
+        let missing: Vec<_> = args
+            .iter()
+            .filter(|arg| find_bytes(&assert.get_output().stdout, arg.as_bytes()).is_none())
+            .map(|arg| format!("`{}`", arg))
+            .collect();
+        if !missing.is_empty() {
+            panic!(
+                "Unexpected stdout, positional {} not echoed, maybe parsed as flags\n{}",
+                missing.join(", "),
+                assert
+            );
+        }
+        assert
+    }
+
+    fn with_path(self, dirs: &[&path::Path]) -> Self {
+        let path = env::join_paths(dirs).unwrap_or_else(|err| panic!("Invalid PATH: {}", err));
+        self.env("PATH", path)
+    }
+
+    fn prepend_path(self, dirs: &[&path::Path]) -> Self {
+        let existing = match self.get_envs().find(|&(key, _)| key == "PATH") {
+            Some((_, value)) => value.map(|value| value.to_owned()),
+            None => env::var_os("PATH"),
+        };
+        let mut paths: Vec<_> = dirs.iter().map(|dir| dir.to_path_buf()).collect();
+        if let Some(existing) = existing {
+            paths.extend(env::split_paths(&existing));
+        }
+        let path = env::join_paths(paths).unwrap_or_else(|err| panic!("Invalid PATH: {}", err));
+        self.env("PATH", path)
+    }
+
+    #[cfg(feature = "tempfile")]
+    fn with_config_file_arg(
+        self,
+        arg: &str,
+        contents: &str,
+    ) -> io::Result<(Self, tempfile::TempPath)> {
+        let mut file = tempfile::Builder::new()
+            .prefix(CONFIG_FILE_PREFIX)
+            .tempfile()?;
+        file.write_all(contents.as_bytes())?;
+        let path = file.into_temp_path();
+        self.arg(arg).arg(&path);
+        Ok((self, path))
+    }
+}
+
+/// File name prefix marking the config files of [`CommandRunExt::with_config_file`], so they are
+/// added to the context of failures.
+///
+/// [`CommandRunExt::with_config_file`]: trait.CommandRunExt.html#method.with_config_file
+pub(crate) const CONFIG_FILE_PREFIX: &str = "assert_cmd-config-";
+
+/// Run `cmd`, killing it if still running after `timeout`.
+pub(crate) fn assert_within(cmd: &mut process::Command, timeout: time::Duration) -> Assert {
+    let running = spawn::spawn(cmd).unwrap();
+    let (assert, exited) = wait_or_kill(running, cmd, timeout);
+    let assert = assert.append_context("timeout", format!("{:?}", timeout));
+    if !exited {
+        panic!("Command timed out after {:?}, killed\n{}", timeout, assert);
+    }
+    assert
+}
+
+/// Wait up to `timeout` for `running` to exit, killing it otherwise.
+///
+/// Returns whether it exited on its own.
+fn wait_or_kill(
+    mut running: spawn::Running,
+    cmd: &process::Command,
+    timeout: time::Duration,
+) -> (Assert, bool) {
+    let exited = running.wait_timeout(timeout).unwrap().is_some();
+    if !exited {
+        running.kill().unwrap();
+    }
+    let (output, timings) = running.wait().unwrap();
+    let assert = Assert::new(output).set_timings(timings).append_command(cmd);
+    (assert, exited)
+}
+
+/// Fail any [`Command`] run on the current thread that doesn't exit within `timeout`, or stop
+/// failing them with `None`.
+///
+/// This applies to every later run on the current thread, like [`OutputAssertExt::assert`] and
+/// those of [`CommandRunExt`], making hang protection a policy for a whole test rather than
+/// something to remember at each call site.  Other threads, like those of other tests, keep their
+/// own default.  Runs with their own timeout, like [`CommandRunExt::assert_within`], use it
+/// instead.
+///
+/// See also [`CommandRunExt::with_default_timeout`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use assert_cmd::prelude::*;
+///
+/// use std::process::Command;
+/// use std::time::Duration;
+///
+/// assert_cmd::run::set_default_timeout(Some(Duration::from_secs(30)));
+/// Command::cargo_bin("bin_fixture")
+///     .unwrap()
+///     .assert()
+///     .success();
+/// ```
+///
+/// [`Command`]: https://doc.rust-lang.org/std/process/struct.Command.html
+/// [`OutputAssertExt::assert`]: ../assert/trait.OutputAssertExt.html#tymethod.assert
+/// [`CommandRunExt`]: trait.CommandRunExt.html
+/// [`CommandRunExt::assert_within`]: trait.CommandRunExt.html#tymethod.assert_within
+/// [`CommandRunExt::with_default_timeout`]: trait.CommandRunExt.html#tymethod.with_default_timeout
+pub fn set_default_timeout(timeout: Option<time::Duration>) {
+    DEFAULT_TIMEOUT.with(|default| default.set(timeout));
+}
+
+/// The timeout set by [`set_default_timeout`] for the current thread, if any.
+pub(crate) fn default_timeout() -> Option<time::Duration> {
+    DEFAULT_TIMEOUT.with(|default| default.get())
+}
+
+/// Kill `running` once it has run for `timeout`, if any.
+///
+/// Returns the timeout if the command was killed, for [`check_timeout`].
+pub(crate) fn kill_after(
+    running: &mut spawn::Running,
+    timeout: Option<time::Duration>,
+) -> Option<time::Duration> {
+    let timeout = timeout?;
+    if running.kill_after(timeout).unwrap() {
+        Some(timeout)
+    } else {
+        None
+    }
+}
+
+/// Fail `assert` if its command was killed by [`kill_after`].
+pub(crate) fn check_timeout(assert: Assert, killed: Option<time::Duration>) -> Assert {
+    match killed {
+        Some(timeout) => {
+            let assert = assert.append_context("timeout", format!("{:?}", timeout));
+            panic!("Command timed out after {:?}, killed\n{}", timeout, assert);
+        }
+        None => assert,
+    }
+}
+
+thread_local! {
+    static DEFAULT_TIMEOUT: cell::Cell<Option<time::Duration>> = const { cell::Cell::new(None) };
+}
+
+/// How `cmd` changes the environment variable `key`, `Some(None)` if removed and `None` if
+/// inherited.
+fn get_env(cmd: &process::Command, key: &str) -> Option<Option<ffi::OsString>> {
+    cmd.get_envs()
+        .find(|&(name, _)| name == key)
+        .map(|(_, value)| value.map(ffi::OsStr::to_owned))
+}
+
+/// Undo any change to `key` on `cmd` since [`get_env`] returned `previous`.
+///
+/// A `Command` can't forget a change, so an inherited variable is set to its current value.
+fn restore_env(cmd: &mut process::Command, key: &str, previous: Option<Option<ffi::OsString>>) {
+    match previous.unwrap_or_else(|| env::var_os(key)) {
+        Some(value) => cmd.env(key, value),
+        None => cmd.env_remove(key),
+    };
+}
+
+/// How far [`CommandRunExt::assert_reproducible_with_epoch`] moves the epoch for its other run.
+const EPOCH_OFFSET: u64 = 366 * 24 * 60 * 60;
+
+/// Create a command running `shell_cmd` with `sh -c`, given the arguments, environment changes,
+/// and current directory of `cmd`.
+pub(crate) fn shell_reference(cmd: &process::Command, shell_cmd: &str) -> process::Command {
+    let mut reference = process::Command::new("sh");
+    reference
+        .arg("-c")
+        .arg(shell_cmd)
+        .arg("sh")
+        .args(cmd.get_args());
+    spawn::copy_environment(cmd, &mut reference);
+    reference
+}
+
+/// The modified time of the entries directly within `dirs`, except those relating to `allowed`.
+fn scan_entries(
+    dirs: &[path::PathBuf],
+    allowed: &path::Path,
+) -> BTreeMap<path::PathBuf, Option<time::SystemTime>> {
+    let mut entries = BTreeMap::new();
+    for dir in dirs {
+        let listing = match fs::read_dir(dir) {
+            Ok(listing) => listing,
+            Err(_) => continue,
+        };
+        for entry in listing.filter_map(Result::ok) {
+            let path = entry.path();
+            // Writes within `allowed` change the modified time of its ancestors.
+            if path.starts_with(allowed) || allowed.starts_with(&path) {
+                continue;
+            }
+            let modified = entry.metadata().and_then(|m| m.modified()).ok();
+            entries.insert(path, modified);
+        }
+    }
+    entries
+}
+
+/// The size and modified time of the files recursively within `root`, with directories
+/// recorded by presence alone since their modified time follows their entries.
+fn scan_tree(root: &path::Path) -> BTreeMap<path::PathBuf, Option<(u64, time::SystemTime)>> {
+    let mut entries = BTreeMap::new();
+    let mut pending = vec![root.to_owned()];
+    while let Some(dir) = pending.pop() {
+        let listing = match fs::read_dir(&dir) {
+            Ok(listing) => listing,
+            Err(_) => continue,
+        };
+        for entry in listing.filter_map(Result::ok) {
+            let path = entry.path();
+            let metadata = match entry.metadata() {
+                Ok(metadata) => metadata,
+                Err(_) => continue,
+            };
+            if metadata.is_dir() {
+                pending.push(path.clone());
+                entries.insert(path, None);
+            } else {
+                let modified = metadata.modified().ok().map(|m| (metadata.len(), m));
+                entries.insert(path, modified);
+            }
+        }
+    }
+    entries
+}
+
+/// Describe the entries added (as `added`), modified, or removed from `before` to `after`.
+fn diff_entries<T: PartialEq>(
+    before: &BTreeMap<path::PathBuf, T>,
+    after: &BTreeMap<path::PathBuf, T>,
+    added: &str,
+) -> Vec<String> {
+    let mut changes = Vec::new();
+    for (entry, state) in after {
+        match before.get(entry) {
+            None => changes.push(format!("{} {}", added, entry.display())),
+            Some(original) if original != state => {
+                changes.push(format!("modified {}", entry.display()))
+            }
+            Some(_) => {}
+        }
+    }
+    for entry in before.keys().filter(|entry| !after.contains_key(*entry)) {
+        changes.push(format!("removed {}", entry.display()));
+    }
+    changes
+}
+
+/// Whether `text` has a version number, like `1.2` or `1.2.3-beta.1`, for
+/// [`CommandRunExt::assert_version`].
+fn has_version(text: &str) -> bool {
+    text.split(|c: char| !(c.is_ascii_alphanumeric() || c == '.'))
+        .map(|word| word.trim_end_matches('.'))
+        .any(|word| {
+            let is_number =
+                |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+            let mut parts = word.split('.');
+            match (parts.next(), parts.next()) {
+                (Some(major), Some(minor)) => is_number(major) && is_number(minor),
+                _ => false,
+            }
+        })
+}
+
+/// Ensure `assert` has the same `stdout`, and optionally code, as `reference`'s `expected`.
+pub(crate) fn matches_reference(
+    assert: Assert,
+    reference: &process::Command,
+    expected: &process::Output,
+    compare_code: bool,
+) -> Assert {
+    let assert = assert.append_context("reference", format!("{:?}", reference));
+    let mut differences = Vec::new();
+    {
+        let actual = assert.get_output();
+        if compare_code && expected.status.code() != actual.status.code() {
+            differences.push(format!(
+                "code differs: reference {:?}, command {:?}",
+                expected.status.code(),
+                actual.status.code()
+            ));
+        }
+        if expected.stdout != actual.stdout {
+            differences.push(format!(
+                "stdout differs, from reference to command:\n{}",
+                diff_buffers(&expected.stdout, &actual.stdout)
+            ));
+        }
+    }
+    if !differences.is_empty() {
+        panic!(
+            "Unexpected output, compared to reference\n{}\n{}",
+            differences.join("\n"),
+            assert
+        );
+    }
+    assert
+}
+
+/// Describe how `actual` differs from `expected`.
+fn compare_output(expected: &process::Output, actual: &process::Output) -> Vec<String> {
+    let mut differences = Vec::new();
+    if expected.status != actual.status {
+        differences.push(format!(
+            "code differs: {:?} != {:?}",
+            expected.status.code(),
+            actual.status.code()
+        ));
+    }
+    if expected.stdout != actual.stdout {
+        differences.push(format!(
+            "stdout differs:\n{}",
+            diff_buffers(&expected.stdout, &actual.stdout)
+        ));
+    }
+    if expected.stderr != actual.stderr {
+        differences.push(format!(
+            "stderr differs:\n{}",
+            diff_buffers(&expected.stderr, &actual.stderr)
+        ));
+    }
+    differences
+}
+
+/// How long a command is given to start before being signaled.
+pub const SIGNAL_DELAY: time::Duration = time::Duration::from_millis(100);
+
+fn sentinel() -> String {
+    let nanos = time::SystemTime::now()
+        .duration_since(time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    format!("assert_cmd-sentinel-{:x}-{:x}", process::id(), nanos)
+}
@@ -0,0 +1,491 @@
+//! Run a [`Command`][Command] while observing its output as it is produced.
+//!
+//! [Command]: https://doc.rust-lang.org/std/process/struct.Command.html
+
+use std::cmp;
+#[cfg(unix)]
+use std::fs;
+use std::io;
+use std::io::Read;
+#[cfg(unix)]
+use std::mem;
+#[cfg(unix)]
+use std::os::raw;
+use std::process;
+use std::thread;
+use std::time;
+
+#[cfg(unix)]
+use libc;
+
+/// When events happened while running a command.
+#[derive(Debug, Clone)]
+pub(crate) struct Timings {
+    pub(crate) spawned: time::Instant,
+    pub(crate) first_stdout: Option<time::Instant>,
+    pub(crate) first_stderr: Option<time::Instant>,
+    /// When each line of stdout was completed by its newline.
+    pub(crate) stdout_lines: Vec<time::Instant>,
+    /// When the command exited, and no earlier than its last output.
+    pub(crate) exited: time::Instant,
+}
+
+impl Timings {
+    /// When the command first wrote to either `stdout` or `stderr`.
+    pub(crate) fn first_output(&self) -> Option<time::Instant> {
+        match (self.first_stdout, self.first_stderr) {
+            (Some(stdout), Some(stderr)) => Some(stdout.min(stderr)),
+            (stdout, stderr) => stdout.or(stderr),
+        }
+    }
+}
+
+/// What was written to each fd beyond stdout and stderr.
+pub(crate) type ExtraFds = Vec<(i32, Vec<u8>)>;
+
+/// Starts a copy of `cmd`, immediately collecting its output in the background.
+///
+/// Like `Command::output`, stdin is null and stdout/stderr are captured.  `cmd` itself is left
+/// untouched, so stdio set on it doesn't apply.
+pub(crate) fn spawn(cmd: &process::Command) -> io::Result<Running> {
+    let mut copy = copy(cmd);
+    copy.stdin(process::Stdio::null());
+    start(copy)
+}
+
+/// Starts a copy of `cmd` like [`spawn`], but with piped stdin for the caller to write.
+pub(crate) fn spawn_with_stdin(
+    cmd: &process::Command,
+) -> io::Result<(Running, process::ChildStdin)> {
+    let mut copy = copy(cmd);
+    copy.stdin(process::Stdio::piped());
+    let mut running = start(copy)?;
+    let stdin = running.child.stdin.take().expect("stdin is piped");
+    Ok((running, stdin))
+}
+
+/// Starts `cmd` with its stdin set, capturing stdout/stderr.
+fn start(mut cmd: process::Command) -> io::Result<Running> {
+    cmd.stdout(process::Stdio::piped());
+    cmd.stderr(process::Stdio::piped());
+
+    let mut child = cmd.spawn()?;
+    let spawned = time::Instant::now();
+    let stdout = child
+        .stdout
+        .take()
+        .map(|stdout| thread::spawn(move || read_timed(stdout)));
+    let stderr = child
+        .stderr
+        .take()
+        .map(|stderr| thread::spawn(move || read_timed(stderr)));
+    Ok(Running {
+        child,
+        spawned,
+        stdout,
+        stderr,
+        extra_fds: vec![],
+    })
+}
+
+/// Starts a copy of `cmd` like [`spawn`], but with piped stdin and leaving stdout for the caller to read.
+///
+/// The returned `Running` collects only stderr.
+pub(crate) fn spawn_interactive(
+    cmd: &process::Command,
+) -> io::Result<(Running, process::ChildStdin, process::ChildStdout)> {
+    let mut copy = copy(cmd);
+    copy.stdin(process::Stdio::piped());
+    copy.stdout(process::Stdio::piped());
+    copy.stderr(process::Stdio::piped());
+
+    let mut child = copy.spawn()?;
+    let spawned = time::Instant::now();
+    let stdin = child.stdin.take().expect("stdin is piped");
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let stderr = child
+        .stderr
+        .take()
+        .map(|stderr| thread::spawn(move || read_timed(stderr)));
+    let running = Running {
+        child,
+        spawned,
+        stdout: None,
+        stderr,
+        extra_fds: vec![],
+    };
+    Ok((running, stdin, stdout))
+}
+
+/// Starts `cmd` like [`spawn`], also collecting what it writes to each of `fds`.
+///
+/// `cmd` is consumed since the hook connecting the fds can't be removed from it, pass a [`copy`].
+#[cfg(unix)]
+pub(crate) fn spawn_with_fds(mut cmd: process::Command, fds: &[i32]) -> io::Result<Running> {
+    use std::os::unix::io::AsRawFd;
+    use std::os::unix::process::CommandExt;
+
+    // Keep the pipes clear of the target fds so `dup2` can't clobber a pipe not yet moved.
+    let min = fds.iter().cloned().max().unwrap_or(2) + 1;
+    let mut pipes = Vec::new();
+    for &fd in fds {
+        let (read, write) = pipe_above(min)?;
+        pipes.push((fd, read, write));
+    }
+    let dups: Vec<_> = pipes
+        .iter()
+        .map(|(fd, _, write)| (write.as_raw_fd(), *fd))
+        .collect();
+
+    unsafe {
+        cmd.pre_exec(move || {
+            for &(write, fd) in &dups {
+                if libc::dup2(write, fd) < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+            Ok(())
+        });
+    }
+    cmd.stdin(process::Stdio::null());
+    let mut running = start(cmd)?;
+    for (fd, read, write) in pipes {
+        drop(write);
+        running
+            .extra_fds
+            .push((fd, thread::spawn(move || read_timed(read))));
+    }
+    Ok(running)
+}
+
+/// Starts a copy of `cmd` like [`spawn`], but with stdout and stderr sharing one pipe, collected
+/// as stdout.
+#[cfg(unix)]
+pub(crate) fn spawn_combined(cmd: &process::Command) -> io::Result<Running> {
+    let (read, write) = pipe_above(3)?;
+    let mut copy = copy(cmd);
+    copy.stdin(process::Stdio::null());
+    copy.stdout(process::Stdio::from(write.try_clone()?));
+    copy.stderr(process::Stdio::from(write));
+    let child = copy.spawn();
+    // Close our copies of the write end, held by `copy`, so the read reaches EOF.
+    drop(copy);
+
+    let child = child?;
+    let spawned = time::Instant::now();
+    Ok(Running {
+        child,
+        spawned,
+        stdout: Some(thread::spawn(move || read_timed(read))),
+        stderr: None,
+        extra_fds: vec![],
+    })
+}
+
+/// Starts `cmd` like [`spawn`], in a new process group led by the command.
+///
+/// `cmd` is consumed since the hook creating the process group can't be removed from it, pass a
+/// [`copy`].
+#[cfg(unix)]
+pub(crate) fn spawn_in_process_group(mut cmd: process::Command) -> io::Result<Running> {
+    use std::os::unix::process::CommandExt;
+
+    unsafe {
+        cmd.pre_exec(|| {
+            if libc::setpgid(0, 0) < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    cmd.stdin(process::Stdio::null());
+    start(cmd)
+}
+
+/// Recreate `cmd` from what is observable of its configuration: its program, arguments,
+/// environment changes and current directory.
+///
+/// Stdio and platform-specific settings, like `pre_exec` hooks, can't be observed and aren't
+/// copied.
+pub(crate) fn copy(cmd: &process::Command) -> process::Command {
+    let mut copy = process::Command::new(cmd.get_program());
+    copy.args(cmd.get_args());
+    copy_environment(cmd, &mut copy);
+    copy
+}
+
+/// Apply the environment changes and current directory of `cmd` to `copy`.
+pub(crate) fn copy_environment(cmd: &process::Command, copy: &mut process::Command) {
+    for (key, value) in cmd.get_envs() {
+        match value {
+            Some(value) => copy.env(key, value),
+            None => copy.env_remove(key),
+        };
+    }
+    if let Some(dir) = cmd.get_current_dir() {
+        copy.current_dir(dir);
+    }
+}
+
+/// Creates a close-on-exec pipe whose fds are at least `min`.
+#[cfg(unix)]
+fn pipe_above(min: i32) -> io::Result<(fs::File, fs::File)> {
+    use std::os::unix::io::FromRawFd;
+
+    let fds = pipe_cloexec()?;
+    let mut moved = [-1; 2];
+    for (fd, moved) in fds.iter().zip(moved.iter_mut()) {
+        *moved = unsafe { libc::fcntl(*fd, libc::F_DUPFD_CLOEXEC, min) };
+    }
+    let err = io::Error::last_os_error();
+    for &fd in fds.iter() {
+        unsafe { libc::close(fd) };
+    }
+    if moved.iter().any(|&fd| fd < 0) {
+        for &fd in moved.iter().filter(|&&fd| 0 <= fd) {
+            unsafe { libc::close(fd) };
+        }
+        return Err(err);
+    }
+    let read = unsafe { fs::File::from_raw_fd(moved[0]) };
+    let write = unsafe { fs::File::from_raw_fd(moved[1]) };
+    Ok((read, write))
+}
+
+/// Creates a pipe that is close-on-exec from the start, so a command spawned by another thread
+/// can't inherit it.
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "linux",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+fn pipe_cloexec() -> io::Result<[raw::c_int; 2]> {
+    let mut fds = [0; 2];
+    if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(fds)
+}
+
+/// Creates a close-on-exec pipe.
+///
+/// Without `pipe2`, a command spawned by another thread before `FD_CLOEXEC` is set can inherit it.
+#[cfg(all(
+    unix,
+    not(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "linux",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))
+))]
+fn pipe_cloexec() -> io::Result<[raw::c_int; 2]> {
+    let mut fds = [0; 2];
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    for &fd in fds.iter() {
+        if unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) } < 0 {
+            let err = io::Error::last_os_error();
+            for &fd in fds.iter() {
+                unsafe { libc::close(fd) };
+            }
+            return Err(err);
+        }
+    }
+    Ok(fds)
+}
+
+/// A command whose output is being collected.
+#[derive(Debug)]
+pub(crate) struct Running {
+    child: process::Child,
+    spawned: time::Instant,
+    stdout: Option<thread::JoinHandle<io::Result<TimedBuffer>>>,
+    stderr: Option<thread::JoinHandle<io::Result<TimedBuffer>>>,
+    extra_fds: Vec<(i32, thread::JoinHandle<io::Result<TimedBuffer>>)>,
+}
+
+impl Running {
+    /// Send `signal` to the command.
+    #[cfg(unix)]
+    pub(crate) fn signal(&self, signal: i32) -> io::Result<()> {
+        let pid = self.child.id() as libc::pid_t;
+        if unsafe { libc::kill(pid, signal) } == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+
+    /// Wait up to `timeout` for the command to exit.
+    ///
+    /// Returns `None` if the command is still running.
+    pub(crate) fn wait_timeout(
+        &mut self,
+        timeout: time::Duration,
+    ) -> io::Result<Option<process::ExitStatus>> {
+        let deadline = time::Instant::now() + timeout;
+        loop {
+            if let Some(status) = self.child.try_wait()? {
+                return Ok(Some(status));
+            }
+            if deadline <= time::Instant::now() {
+                return Ok(None);
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// Kill the command if it is still running `timeout` after being spawned.
+    ///
+    /// Returns whether it was killed.
+    pub(crate) fn kill_after(&mut self, timeout: time::Duration) -> io::Result<bool> {
+        let remaining = timeout
+            .checked_sub(self.spawned.elapsed())
+            .unwrap_or_default();
+        if self.wait_timeout(remaining)?.is_some() {
+            return Ok(false);
+        }
+        self.kill()?;
+        Ok(true)
+    }
+
+    /// The command's process id.
+    #[cfg(unix)]
+    pub(crate) fn id(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Forcibly kill the command.
+    pub(crate) fn kill(&mut self) -> io::Result<()> {
+        self.child.kill()
+    }
+
+    /// Wait for the command to exit, collecting all of its output.
+    pub(crate) fn wait(self) -> io::Result<(process::Output, Timings)> {
+        self.wait_with_fds()
+            .map(|(output, timings, _)| (output, timings))
+    }
+
+    /// Wait for the command to exit, collecting all of its output, including extra fds.
+    pub(crate) fn wait_with_fds(mut self) -> io::Result<(process::Output, Timings, ExtraFds)> {
+        let status = self.child.wait()?;
+        let exited = time::Instant::now();
+        self.collect(status, exited)
+    }
+
+    /// Wait for the command to exit, collecting all of its output and its peak memory use in
+    /// bytes.
+    #[cfg(unix)]
+    pub(crate) fn wait_with_max_rss(self) -> io::Result<(process::Output, Timings, u64)> {
+        use std::os::unix::process::ExitStatusExt;
+
+        // `Child::wait` discards the resource usage, so reap the command ourselves.
+        let pid = self.child.id() as libc::pid_t;
+        let mut status = 0;
+        let mut usage: libc::rusage = unsafe { mem::zeroed() };
+        while unsafe { libc::wait4(pid, &mut status, 0, &mut usage) } < 0 {
+            let err = io::Error::last_os_error();
+            if err.kind() != io::ErrorKind::Interrupted {
+                return Err(err);
+            }
+        }
+        let exited = time::Instant::now();
+        let status = process::ExitStatus::from_raw(status);
+        let (output, timings, _) = self.collect(status, exited)?;
+        Ok((output, timings, max_rss_bytes(usage.ru_maxrss)))
+    }
+
+    fn collect(
+        self,
+        status: process::ExitStatus,
+        exited: time::Instant,
+    ) -> io::Result<(process::Output, Timings, ExtraFds)> {
+        let stdout = join(self.stdout)?;
+        let stderr = join(self.stderr)?;
+        // The readers can see the last of the output just after the exit was observed.
+        let exited = stdout
+            .last
+            .into_iter()
+            .chain(stderr.last)
+            .fold(exited, cmp::max);
+        let mut extra_fds = Vec::new();
+        for (fd, handle) in self.extra_fds {
+            extra_fds.push((fd, join(Some(handle))?.buffer));
+        }
+
+        let output = process::Output {
+            status,
+            stdout: stdout.buffer,
+            stderr: stderr.buffer,
+        };
+        let timings = Timings {
+            spawned: self.spawned,
+            first_stdout: stdout.first,
+            first_stderr: stderr.first,
+            stdout_lines: stdout.lines,
+            exited,
+        };
+        Ok((output, timings, extra_fds))
+    }
+}
+
+// `ru_maxrss` is in bytes on Apple platforms and kilobytes elsewhere.
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn max_rss_bytes(max_rss: raw::c_long) -> u64 {
+    max_rss as u64
+}
+
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "ios"))))]
+fn max_rss_bytes(max_rss: raw::c_long) -> u64 {
+    max_rss as u64 * 1024
+}
+
+/// How often [`Running::wait_timeout`] checks whether the command exited.
+const POLL_INTERVAL: time::Duration = time::Duration::from_millis(10);
+
+#[derive(Debug, Default)]
+struct TimedBuffer {
+    buffer: Vec<u8>,
+    first: Option<time::Instant>,
+    last: Option<time::Instant>,
+    lines: Vec<time::Instant>,
+}
+
+fn read_timed<R>(mut reader: R) -> io::Result<TimedBuffer>
+where
+    R: Read,
+{
+    let mut timed = TimedBuffer::default();
+    let mut chunk = [0; 8 * 1024];
+    loop {
+        let read = match reader.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        let now = time::Instant::now();
+        if timed.first.is_none() {
+            timed.first = Some(now);
+        }
+        timed.last = Some(now);
+        let lines = chunk[..read].iter().filter(|&&byte| byte == b'\n').count();
+        timed.lines.extend((0..lines).map(|_| now));
+        timed.buffer.extend_from_slice(&chunk[..read]);
+    }
+    Ok(timed)
+}
+
+fn join(handle: Option<thread::JoinHandle<io::Result<TimedBuffer>>>) -> io::Result<TimedBuffer> {
+    match handle {
+        Some(handle) => handle.join().expect("Couldn't join output reader"),
+        None => Ok(TimedBuffer::default()),
+    }
+}
@@ -8,7 +8,10 @@ use std::io::Read;
 use std::io::Write;
 use std::path;
 use std::process;
+use std::thread;
+use std::time;
 
+use assert::first_difference;
 use assert::Assert;
 use assert::OutputAssertExt;
 use cmd::dump_buffer;
@@ -16,6 +19,10 @@ use cmd::DebugBuffer;
 use cmd::OutputError;
 use cmd::OutputOkExt;
 use cmd::OutputResult;
+use run;
+use run::matches_reference;
+use run::shell_reference;
+use spawn;
 
 /// Write to `stdin` of a [`Command`][Command].
 ///
@@ -168,10 +175,134 @@ impl<'a> StdInCommand<'a> {
     /// [Command]: https://doc.rust-lang.org/std/process/struct.Command.html
     /// [Command_output]: https://doc.rust-lang.org/std/process/struct.Command.html#method.output
     pub fn output(&mut self) -> io::Result<process::Output> {
-        self.spawn()?.wait_with_output()
+        self.output_impl().map(|(output, _)| output)
     }
 
-    fn spawn(&mut self) -> io::Result<process::Child> {
+    /// Run the command, also reporting whether all of stdin was written to it.
+    fn output_impl(&mut self) -> io::Result<(process::Output, bool)> {
+        let (child, writer) = self.spawn()?;
+        let output = child.wait_with_output()?;
+        let consumed = stdin_consumed(writer)?;
+        Ok((output, consumed))
+    }
+
+    /// Ensure the command writes the same `stdout` as `reference`, given the same stdin.
+    ///
+    /// See [`CommandRunExt::assert_matches_command`][assert_matches_command].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("cat")
+    ///     .with_stdin()
+    ///     .buffer("42")
+    ///     .assert_matches_command(&mut Command::new("cat"))
+    ///     .success();
+    /// ```
+    ///
+    /// [assert_matches_command]: ../run/trait.CommandRunExt.html#tymethod.assert_matches_command
+    pub fn assert_matches_command(&mut self, reference: &mut process::Command) -> Assert {
+        let expected = self.reference_output(reference);
+        matches_reference(self.assert_impl(), reference, &expected, false)
+    }
+
+    /// Ensure the command writes the same `stdout` and returns the same code as `reference`,
+    /// given the same stdin.
+    ///
+    /// See [`CommandRunExt::assert_matches_command_exactly`][assert_matches_command_exactly].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("cat")
+    ///     .with_stdin()
+    ///     .buffer("42")
+    ///     .assert_matches_command_exactly(&mut Command::new("cat"));
+    /// ```
+    ///
+    /// [assert_matches_command_exactly]: ../run/trait.CommandRunExt.html#tymethod.assert_matches_command_exactly
+    pub fn assert_matches_command_exactly(&mut self, reference: &mut process::Command) -> Assert {
+        let expected = self.reference_output(reference);
+        matches_reference(self.assert_impl(), reference, &expected, true)
+    }
+
+    /// Ensure the command writes the same `stdout` and returns the same code as the shell command
+    /// `shell_cmd`, given the same stdin.
+    ///
+    /// See [`CommandRunExt::assert_matches_shell`][assert_matches_shell].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use assert_cmd::prelude::*;
+    ///
+    /// use std::process::Command;
+    ///
+    /// Command::new("cat")
+    ///     .with_stdin()
+    ///     .buffer("42")
+    ///     .assert_matches_shell("cat");
+    /// ```
+    ///
+    /// [assert_matches_shell]: ../run/trait.CommandRunExt.html#tymethod.assert_matches_shell
+    pub fn assert_matches_shell(&mut self, shell_cmd: &str) -> Assert {
+        let mut reference = shell_reference(self.cmd, shell_cmd);
+        self.assert_matches_command_exactly(&mut reference)
+    }
+
+    fn assert_impl(&mut self) -> Assert {
+        if let Some(timeout) = run::default_timeout() {
+            return self.assert_within(timeout);
+        }
+        let (output, consumed) = self.output_impl().unwrap();
+        Assert::new(output)
+            .set_stdin_consumed(consumed)
+            .append_command(self.cmd)
+            .append_context("stdin", DebugBuffer::new(self.stdin.clone()))
+    }
+
+    /// Like [`CommandRunExt::assert_within`], for the default timeout.
+    ///
+    /// [`CommandRunExt::assert_within`]: ../run/trait.CommandRunExt.html#tymethod.assert_within
+    fn assert_within(&mut self, timeout: time::Duration) -> Assert {
+        let (mut running, mut stdin) = spawn::spawn_with_stdin(self.cmd).unwrap();
+        let buffer = self.stdin.clone();
+        let writer = thread::spawn(move || stdin.write_all(&buffer));
+        let exited = running.wait_timeout(timeout).unwrap().is_some();
+        if !exited {
+            running.kill().unwrap();
+        }
+        let (output, timings) = running.wait().unwrap();
+        let consumed = stdin_consumed(writer).unwrap();
+        let assert = Assert::new(output)
+            .set_timings(timings)
+            .set_stdin_consumed(consumed)
+            .append_command(self.cmd)
+            .append_context("stdin", DebugBuffer::new(self.stdin.clone()))
+            .append_context("timeout", format!("{:?}", timeout));
+        if !exited {
+            panic!("Command timed out after {:?}, killed\n{}", timeout, assert);
+        }
+        assert
+    }
+
+    fn reference_output(&self, reference: &mut process::Command) -> process::Output {
+        reference
+            .with_stdin()
+            .buffer(self.stdin.clone())
+            .output()
+            .unwrap()
+    }
+
+    fn spawn(&mut self) -> io::Result<(process::Child, thread::JoinHandle<io::Result<()>>)> {
         // stdout/stderr should only be piped for `output` according to `process::Command::new`.
         self.cmd.stdin(process::Stdio::piped());
         self.cmd.stdout(process::Stdio::piped());
@@ -179,12 +310,21 @@ impl<'a> StdInCommand<'a> {
 
         let mut spawned = self.cmd.spawn()?;
 
-        spawned
-            .stdin
-            .as_mut()
-            .expect("Couldn't get mut ref to command stdin")
-            .write_all(&self.stdin)?;
-        Ok(spawned)
+        // Write in the background so a command filling its output pipes can't deadlock us.
+        let mut stdin = spawned.stdin.take().expect("Couldn't get command stdin");
+        let buffer = self.stdin.clone();
+        let writer = thread::spawn(move || stdin.write_all(&buffer));
+        Ok((spawned, writer))
+    }
+}
+
+/// Whether `writer` wrote all of stdin.
+fn stdin_consumed(writer: thread::JoinHandle<io::Result<()>>) -> io::Result<bool> {
+    // The command closing stdin early isn't an error in running it.
+    match writer.join().expect("Couldn't join stdin writer") {
+        Ok(()) => Ok(true),
+        Err(ref err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(false),
+        Err(err) => Err(err),
     }
 }
 
@@ -216,9 +356,52 @@ impl<'c, 'a> OutputOkExt for &'c mut StdInCommand<'a> {
 
 impl<'c> OutputAssertExt for &'c mut StdInCommand<'c> {
     fn assert(self) -> Assert {
-        let output = self.output().unwrap();
-        Assert::new(output)
-            .append_context("command", format!("{:?}", self.cmd))
-            .append_context("stdin", DebugBuffer::new(self.stdin.clone()))
+        self.assert_impl()
+    }
+}
+
+/// Ensure piping `input` through `encode`, then through `decode`, gives back `input`.
+///
+/// Both commands must succeed.  Failures report the first offset where the decoded output
+/// differs from `input`.  The `Assert` for `decode` is returned.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::process::Command;
+///
+/// assert_cmd::stdin::assert_roundtrip(
+///     Command::new("gzip").arg("-c"),
+///     Command::new("gzip").arg("-dc"),
+///     b"hello world",
+/// )
+/// .success();
+/// ```
+pub fn assert_roundtrip(
+    encode: &mut process::Command,
+    decode: &mut process::Command,
+    input: &[u8],
+) -> Assert {
+    let encoded = encode
+        .with_stdin()
+        .buffer(input.to_vec())
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let assert = decode
+        .with_stdin()
+        .buffer(encoded)
+        .assert()
+        .append_context("encode", format!("{:?}", encode))
+        .success();
+    let difference = first_difference(&assert.get_output().stdout, input).unwrap();
+    if let Some(offset) = difference {
+        panic!(
+            "Unexpected roundtrip, decoded output differs from input at byte {}\n{}",
+            offset, assert
+        );
     }
+    assert
 }
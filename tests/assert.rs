@@ -1,7 +1,14 @@
 extern crate assert_cmd;
+#[cfg(unix)]
+extern crate libc;
 extern crate predicates;
 
+use std::env;
+use std::fs;
+use std::process;
 use std::process::Command;
+use std::process::Output;
+use std::time::Duration;
 
 use assert_cmd::prelude::*;
 use predicates::prelude::*;
@@ -17,6 +24,18 @@ fn stdout_string() {
         .stdout(expected);
 }
 
+#[test]
+fn stdout_string_ref() {
+    let expected = "hello\n".to_owned();
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .env("stderr", "world")
+        .assert()
+        .stdout(&expected)
+        .stdout(&expected);
+}
+
 #[test]
 fn trait_example() {
     let mut cmd = Command::cargo_bin("bin_fixture").unwrap();
@@ -143,3 +162,1372 @@ fn stderr_example() {
         .assert()
         .stderr("world\n");
 }
+
+#[test]
+#[cfg(unix)]
+fn raw_status_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "42")
+        .assert()
+        .raw_status(42 << 8);
+}
+
+#[test]
+fn stdout_eq_reader_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .env("stderr", "world")
+        .assert()
+        .stdout_eq_reader(fs::File::open("tests/fixtures/hello.txt").unwrap())
+        .stderr_eq_reader(fs::File::open("tests/fixtures/world.txt").unwrap());
+}
+
+#[test]
+#[should_panic(expected = "differs from reader at offset 5")]
+fn stdout_eq_reader_mismatch() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello world")
+        .assert()
+        .stdout_eq_reader(fs::File::open("tests/fixtures/hello.txt").unwrap());
+}
+
+#[test]
+fn assert_leaves_stdio_untouched() {
+    let mut cmd = Command::cargo_bin("bin_fixture").unwrap();
+    cmd.assert().success();
+    let mut child = cmd.spawn().unwrap();
+    assert!(child.stdin.is_none());
+    assert!(child.stdout.is_none());
+    assert!(child.stderr.is_none());
+    child.wait().unwrap();
+}
+
+#[test]
+fn spawn_to_first_byte_and_total() {
+    let assert = Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("sleep", "100")
+        .env("stderr", "hello")
+        .assert();
+    let first = assert.spawn_to_first_byte().unwrap();
+    assert!(Duration::from_millis(100) <= first);
+    assert!(first <= assert.total());
+
+    let assert = Command::cargo_bin("bin_fixture").unwrap().assert();
+    assert_eq!(assert.spawn_to_first_byte(), None);
+}
+
+#[test]
+fn first_byte_within_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .assert()
+        .first_byte_within(Duration::from_secs(5));
+}
+
+#[test]
+#[should_panic(expected = "Unexpected latency")]
+fn first_byte_within_late() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("sleep", "500")
+        .env("stdout", "hello")
+        .assert()
+        .first_byte_within(Duration::from_millis(10));
+}
+
+#[test]
+#[should_panic(expected = "program=`sh`\nargc=`2`")]
+fn command_context_shell() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo hello 1>&2; exit 1")
+        .assert()
+        .success();
+}
+
+#[test]
+fn code_with_example() {
+    let expected = "42";
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", expected)
+        .assert()
+        .code_with(|| expected.parse().unwrap());
+}
+
+#[test]
+#[should_panic(expected = "Unexpected return code 42, failed var == 7")]
+fn code_with_mismatch() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "42")
+        .assert()
+        .code_with(|| 3 + 4);
+}
+
+#[test]
+#[should_panic(expected = "Unexpected return code 7, failed var in [2, 42]")]
+fn code_lists_allowed_codes() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "7")
+        .assert()
+        .code(&[2, 42] as &[i32]);
+}
+
+#[test]
+fn is_not_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .env("stderr", "world")
+        .assert()
+        .stdout_is_not("goodbye\n")
+        .stderr_is_not(b"error\n" as &[u8]);
+}
+
+#[test]
+#[should_panic(expected = "Unexpected stdout, unexpectedly matched excluded value")]
+fn stdout_is_not_matched() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .assert()
+        .stdout_is_not("hello\n");
+}
+
+#[test]
+#[cfg(windows)]
+#[should_panic(expected = "code=-1073741819 (0xc0000005)")]
+fn windows_code_hex() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "-1073741819")
+        .assert()
+        .success();
+}
+
+#[test]
+fn into_output_example() {
+    let output = Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .assert()
+        .success()
+        .into_output();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"hello\n");
+}
+
+#[test]
+fn to_snapshot_example() {
+    let snapshot = Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .assert()
+        .to_snapshot();
+    assert_eq!(snapshot, "code: 0\nstdout:\nhello\nstderr:\n");
+}
+
+#[test]
+fn into_string_example() {
+    let stdout = Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "42")
+        .assert()
+        .success()
+        .into_stdout_string();
+    assert_eq!(stdout, "42\n");
+
+    let stderr = Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stderr", "42")
+        .assert()
+        .success()
+        .try_into_stderr_string()
+        .unwrap();
+    assert_eq!(stderr, "42\n");
+}
+
+#[test]
+fn try_into_string_invalid() {
+    let output = || Output {
+        status: Command::cargo_bin("bin_fixture").unwrap().status().unwrap(),
+        stdout: vec![b'a', 0xff],
+        stderr: vec![],
+    };
+    assert!(output().assert().try_into_stdout_string().is_err());
+    assert_eq!(output().assert().into_stdout_string(), "a\u{fffd}");
+}
+
+#[test]
+#[should_panic(expected = "right: var - original <= 0\n        original: world\n")]
+fn show_parameters() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .assert()
+        .show_parameters()
+        .stdout(predicate::str::contains("hello").and(predicate::str::similar("world\n")));
+}
+
+#[test]
+fn stdout_starts_with_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello world")
+        .assert()
+        .stdout_starts_with("hello");
+}
+
+#[test]
+fn stdout_ends_with_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello world")
+        .assert()
+        .stdout_ends_with("world\n");
+}
+
+#[test]
+fn stderr_starts_with_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stderr", "hello world")
+        .assert()
+        .stderr_starts_with("hello");
+}
+
+#[test]
+fn stderr_ends_with_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stderr", "hello world")
+        .assert()
+        .stderr_ends_with("world\n");
+}
+
+#[test]
+#[should_panic(expected = "actual start=```goodb```")]
+fn stdout_starts_with_mismatch() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "goodbye world")
+        .assert()
+        .stdout_starts_with("hello");
+}
+
+#[test]
+#[should_panic(expected = "actual end=```orld\n```")]
+fn stderr_ends_with_mismatch() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stderr", "hello world")
+        .assert()
+        .stderr_ends_with("moon\n");
+}
+
+#[test]
+fn stdout_differs_from_path_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "goodbye")
+        .assert()
+        .stdout_differs_from_path("tests/fixtures/hello.txt");
+}
+
+#[test]
+#[should_panic(expected = "output unexpectedly unchanged from tests/fixtures/hello.txt")]
+fn stdout_differs_from_path_unchanged() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .assert()
+        .stdout_differs_from_path("tests/fixtures/hello.txt");
+}
+
+#[test]
+#[should_panic(expected = "Failed to read tests/fixtures/missing.txt")]
+fn stdout_differs_from_path_missing() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .assert()
+        .stdout_differs_from_path("tests/fixtures/missing.txt");
+}
+
+fn code_is_line_count(output: &Output) -> Result<(), String> {
+    let lines = output
+        .stdout
+        .split(|b| *b == b'\n')
+        .filter(|l| !l.is_empty());
+    let count = lines.count() as i32;
+    if output.status.code() == Some(count) {
+        Ok(())
+    } else {
+        Err(format!("code is not the line count, {}", count))
+    }
+}
+
+#[test]
+fn relate_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "a\nb")
+        .env("exit", "2")
+        .assert()
+        .relate(code_is_line_count);
+}
+
+#[test]
+#[should_panic(expected = "Unexpected output, code is not the line count, 3")]
+fn relate_fails() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "a\nb\nc")
+        .env("exit", "2")
+        .assert()
+        .relate(code_is_line_count);
+}
+
+#[test]
+fn assert_no_leak_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "token accepted")
+        .assert()
+        .assert_no_leak(&["hunter2-s3cr3t"]);
+}
+
+#[test]
+#[should_panic(expected = "Leaked `hu<redacted, 14 chars>` to stderr at offset 7")]
+fn assert_no_leak_leaked() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stderr", "token: hunter2-s3cr3t")
+        .assert()
+        .assert_no_leak(&["hunter2-s3cr3t"]);
+}
+
+#[test]
+fn assert_no_leak_redacts_output() {
+    let result = std::panic::catch_unwind(|| {
+        Command::cargo_bin("bin_fixture")
+            .unwrap()
+            .env("stdout", "hunter2-s3cr3t")
+            .assert()
+            .assert_no_leak(&["hunter2-s3cr3t"]);
+    });
+    let message = result.unwrap_err().downcast::<String>().unwrap();
+    assert!(!message.contains("hunter2"), "{}", message);
+}
+
+#[test]
+fn assert_no_leak_redacts_elided_output() {
+    let result = std::panic::catch_unwind(|| {
+        Command::cargo_bin("bin_fixture")
+            .unwrap()
+            .env("stdout", "token: hunter2-s3cr3t")
+            .assert()
+            .with_line_width(16)
+            .assert_no_leak(&["hunter2-s3cr3t"]);
+    });
+    let message = result.unwrap_err().downcast::<String>().unwrap();
+    assert!(!message.contains("hunter"), "{}", message);
+}
+
+#[test]
+fn assert_no_leak_redacts_context() {
+    let result = std::panic::catch_unwind(|| {
+        Command::cargo_bin("bin_fixture")
+            .unwrap()
+            .env("stdout", "hunter2-s3cr3t")
+            .assert()
+            .append_context("token", "hunter2-s3cr3t")
+            .assert_no_leak(&["hunter2-s3cr3t"]);
+    });
+    let message = result.unwrap_err().downcast::<String>().unwrap();
+    assert!(!message.contains("hunter"), "{}", message);
+}
+
+#[test]
+fn assert_no_leak_keeps_redactions() {
+    let result = std::panic::catch_unwind(|| {
+        Command::cargo_bin("bin_fixture")
+            .unwrap()
+            .env("stdout", "hunter2-s3cr3t re")
+            .assert()
+            .assert_no_leak(&["re", "hunter2-s3cr3t"]);
+    });
+    let message = result.unwrap_err().downcast::<String>().unwrap();
+    assert!(
+        message.contains("hu<redacted, 14 chars> <redacted, 2 chars>"),
+        "{}",
+        message
+    );
+    assert!(
+        message.contains("Leaked `<redacted, 2 chars>`"),
+        "{}",
+        message
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn assert_no_leak_redacts_binary_output() {
+    let result = std::panic::catch_unwind(|| {
+        Command::new("printf")
+            .arg(r"\377 hunter2-s3cr3t")
+            .assert()
+            .assert_no_leak(&["hunter2-s3cr3t"]);
+    });
+    let message = result.unwrap_err().downcast::<String>().unwrap();
+    assert!(!message.contains("hunter"), "{}", message);
+    assert!(!message.contains("104, 117, 110"), "{}", message);
+}
+
+#[test]
+fn stdout_within_distance_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "tool 1.2.4")
+        .assert()
+        .stdout_within_distance("tool 1.2.3\n", 1);
+}
+
+#[test]
+fn stderr_within_distance_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stderr", "tool 1.2.4")
+        .assert()
+        .stderr_within_distance("tool 1.2.3\n", 1);
+}
+
+#[test]
+#[should_panic(expected = "Unexpected stdout, 3 edits from expected, more than 1")]
+fn stdout_within_distance_too_far() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "tool 2.0.0")
+        .assert()
+        .stdout_within_distance("tool 1.2.3\n", 1);
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "Unexpected failure.\ncode=<interrupted>\nsignal=6\n")]
+fn success_interrupted_shows_stdout() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo partial; kill -ABRT $$")
+        .assert()
+        .success();
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "code=<interrupted>\nsignal=6\nstdout=```partial\n```")]
+fn interrupted_shows_signal() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo partial; kill -ABRT $$")
+        .assert()
+        .interrupted()
+        .stdout("complete\n");
+}
+
+#[test]
+fn nonzero_code_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "2")
+        .assert()
+        .nonzero_code();
+}
+
+#[test]
+#[should_panic(expected = "Unexpected success, returned code 0")]
+fn nonzero_code_zero() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .assert()
+        .nonzero_code();
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "Unexpected interruption, no code returned")]
+fn nonzero_code_interrupted() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("kill -TERM $$")
+        .assert()
+        .nonzero_code();
+}
+
+#[test]
+fn stdout_sorted_eq_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "b\na\nb")
+        .assert()
+        .stdout_sorted_eq("a\nb\nb\n");
+}
+
+#[test]
+fn stderr_sorted_eq_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stderr", "b\na\nb")
+        .assert()
+        .stderr_sorted_eq("a\nb\nb\n");
+}
+
+#[test]
+#[should_panic(expected = "Unexpected stdout, sorted lines differ")]
+fn stdout_sorted_eq_multiplicity() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "b\na")
+        .assert()
+        .stdout_sorted_eq("a\nb\nb\n");
+}
+
+#[test]
+fn get_output_mut_example() {
+    let mut assert = Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello  ")
+        .assert()
+        .success();
+    let stdout = &mut assert.get_output_mut().stdout;
+    while stdout.ends_with(b" ") || stdout.ends_with(b"\n") {
+        stdout.pop();
+    }
+    assert.stdout("hello");
+}
+
+#[test]
+#[cfg(windows)]
+fn stdout_utf16_example() {
+    Command::new("cmd")
+        .args(["/U", "/C", "echo hello"])
+        .assert()
+        .stdout_utf16("hello\r\n");
+}
+
+#[test]
+fn output_contains_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stderr", "warning: deprecated")
+        .assert()
+        .output_contains("deprecated");
+}
+
+#[test]
+#[should_panic(expected = "`hello` in neither stdout nor stderr")]
+fn output_contains_not_across_streams() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hel")
+        .env("stderr", "lo world")
+        .assert()
+        .output_contains("world")
+        .output_contains("hello");
+}
+
+#[test]
+#[cfg(unix)]
+fn stdout_tail_example() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo compiling; echo compiling; echo 2 passed; echo 0 failed")
+        .assert()
+        .stdout_tail(2, predicate::str::contains("0 failed"))
+        .stdout_tail(2, predicate::str::contains("compiling").not())
+        .stdout_tail(10, predicate::str::starts_with("compiling"));
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "tail=```1 failed\n```")]
+fn stdout_tail_failure() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo 1 passed; echo 1 failed")
+        .assert()
+        .stdout_tail(1, predicate::str::contains("0 failed"));
+}
+
+#[test]
+#[cfg(unix)]
+fn assert_help_sections_example() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("printf 'USAGE:\\n    prog [OPTIONS]\\n\\nOPTIONS:\\n    -h, --help\\n'")
+        .assert()
+        .assert_help_sections(&["USAGE", "OPTIONS"]);
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(
+    expected = "Unexpected help sections, found `USAGE` at line 4, `OPTIONS` at line 1, `ARGS` missing"
+)]
+fn assert_help_sections_out_of_order() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("printf 'Options:\\n    -h\\n\\nUsage: prog\\n'")
+        .assert()
+        .assert_help_sections(&["USAGE", "OPTIONS", "ARGS"]);
+}
+
+#[test]
+#[cfg(unix)]
+fn stdout_final_line_example() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("printf ' 50%%\\r100%%\\ndone\\n'")
+        .assert()
+        .stdout_final_line("100%\ndone\n");
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "Unexpected stderr, after carriage returns\ndisplayed=```99%\n```")]
+fn stderr_final_line_mismatch() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("printf ' 50%%\\r99%%\\n' >&2")
+        .assert()
+        .stderr_final_line("100%\n");
+}
+
+#[test]
+#[cfg(unix)]
+fn stdout_without_trailing_example() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("printf 'hello\\n> '")
+        .assert()
+        .stdout_without_trailing("> ", "hello\n");
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "Unexpected stderr, with trailer `> ` not found")]
+fn stderr_without_trailing_missing() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("printf 'hello\\n$ ' >&2")
+        .assert()
+        .stderr_without_trailing("> ", "hello\n");
+}
+
+#[test]
+#[cfg(unix)]
+fn stdout_range_example() {
+    Command::new("printf")
+        .arg("\\177ELF\\002")
+        .assert()
+        .stdout_range(0..4, b"\x7fELF" as &[u8])
+        .stdout_range(4..5, predicate::eq(&[2u8] as &[u8]));
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "Unexpected stdout, bytes 0..4 = [7f 45 4c 46] failed")]
+fn stdout_range_mismatch() {
+    Command::new("printf")
+        .arg("\\177ELF\\002")
+        .assert()
+        .stdout_range(0..4, b"\x7fELG" as &[u8]);
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "Unexpected stdout, range 4..8 is out of bounds of 5 bytes")]
+fn stdout_range_out_of_bounds() {
+    Command::new("printf")
+        .arg("\\177ELF\\002")
+        .assert()
+        .stdout_range(4..8, b"\x02" as &[u8]);
+}
+
+#[test]
+fn stdout_numeric_tolerant_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "mean: 0.30000000000000004")
+        .assert()
+        .stdout_numeric_tolerant("mean: 0.3", 1e-9);
+}
+
+#[test]
+#[should_panic(expected = "Unexpected stderr, token 1 `0.31` differs from expected `0.3`")]
+fn stderr_numeric_tolerant_beyond() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stderr", "mean: 0.31")
+        .assert()
+        .stderr_numeric_tolerant("mean: 0.3", 1e-9);
+}
+
+#[test]
+#[cfg(unix)]
+fn stdout_has_bom_example() {
+    Command::new("printf")
+        .arg("\\357\\273\\277name,count\\n")
+        .assert()
+        .stdout_has_bom();
+}
+
+#[test]
+#[should_panic(expected = "Unexpected stdout, no byte-order mark, starts with [6e 61 6d 65]")]
+fn stdout_has_bom_missing() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "name,count")
+        .assert()
+        .stdout_has_bom();
+}
+
+#[test]
+fn stdout_no_bom_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "name,count")
+        .assert()
+        .stdout_no_bom();
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "Unexpected stdout, starts with UTF-16LE byte-order mark [ff fe]")]
+fn stdout_no_bom_found() {
+    Command::new("printf")
+        .arg("\\377\\376h\\000")
+        .assert()
+        .stdout_no_bom();
+}
+
+#[test]
+#[cfg(unix)]
+fn max_line_interval_example() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("for i in 1 2 3; do echo progress $i; sleep 0.1; done")
+        .assert()
+        .max_line_interval(Duration::from_secs(1));
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "before stdout line 3 `progress 3`, expected within 200ms")]
+fn max_line_interval_stalled() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo progress 1; echo progress 2; sleep 0.5; echo progress 3")
+        .assert()
+        .max_line_interval(Duration::from_millis(200));
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "before stdout line 1 `progress 1`, expected within 200ms")]
+fn max_line_interval_slow_start() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("sleep 0.5; echo progress 1")
+        .assert()
+        .max_line_interval(Duration::from_millis(200));
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "before exiting, expected within 200ms")]
+fn max_line_interval_slow_exit() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo progress 1; sleep 0.5")
+        .assert()
+        .max_line_interval(Duration::from_millis(200));
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "error: not valid UTF-8 (invalid byte at offset 2)")]
+fn stdout_str_invalid_utf8() {
+    Command::new("printf")
+        .arg("he\\377llo")
+        .assert()
+        .stdout("hello");
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "error: not valid UTF-8 (invalid byte at offset 0)")]
+fn stderr_str_predicate_invalid_utf8() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("printf '\\377' >&2")
+        .assert()
+        .stderr(predicate::str::contains("hello"));
+}
+
+#[test]
+fn total_output_below_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .env("stderr", "world")
+        .assert()
+        .total_output_below(1024);
+}
+
+#[test]
+#[should_panic(
+    expected = "12 bytes in total (stdout 6 bytes, stderr 6 bytes), expected below 12 bytes"
+)]
+fn total_output_below_exceeded() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .env("stderr", "world")
+        .assert()
+        .total_output_below(12);
+}
+
+#[test]
+#[cfg(unix)]
+fn code_errno_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "2")
+        .assert()
+        .code_errno(libc::ENOENT);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+#[should_panic(
+    expected = "Unexpected return code 13 (Permission denied (os error 13)), expected errno 2 (No such file or directory (os error 2))"
+)]
+fn code_errno_mismatch() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "13")
+        .assert()
+        .code_errno(libc::ENOENT);
+}
+
+#[test]
+#[cfg(unix)]
+fn stdin_fully_consumed_example() {
+    Command::new("cat")
+        .with_stdin()
+        .buffer("42")
+        .assert()
+        .stdin_fully_consumed();
+}
+
+#[test]
+#[cfg(unix)]
+fn assert_roundtrip_example() {
+    assert_cmd::stdin::assert_roundtrip(
+        Command::new("tr").args(["a-z", "n-za-m"]),
+        Command::new("tr").args(["a-z", "n-za-m"]),
+        b"hello world",
+    )
+    .stdout("hello world");
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "decoded output differs from input at byte 1")]
+fn assert_roundtrip_lossy() {
+    assert_cmd::stdin::assert_roundtrip(
+        Command::new("tr").args(["a-z", "n-za-m"]),
+        &mut Command::new("cat"),
+        b"Hello",
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn consumed_all_stdin_closed_early() {
+    let assert = Command::new("head")
+        .arg("-c1")
+        .with_stdin()
+        .buffer(vec![b'x'; 4 * 1024 * 1024])
+        .assert()
+        .success()
+        .stdout("x");
+    assert!(!assert.consumed_all_stdin());
+}
+
+#[test]
+#[should_panic(expected = "Stdin consumption not tracked")]
+fn consumed_all_stdin_untracked() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .assert()
+        .consumed_all_stdin();
+}
+
+#[test]
+fn stdout_contains_count_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "warning: a\nwarning: b\nwarning: c")
+        .assert()
+        .stdout_contains_count("warning:", 3);
+}
+
+#[test]
+fn stderr_contains_count_matching_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stderr", "warning: a\nwarning: b\nwarning: c")
+        .assert()
+        .stderr_contains_count_matching("warning:", predicate::ge(2));
+}
+
+#[test]
+fn stderr_max_level_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stderr", "INFO starting\n[WARN] slow\ndone")
+        .assert()
+        .stderr_max_level("warn");
+}
+
+#[test]
+#[should_panic(expected = "Unexpected stderr, 1 lines above INFO\n[WARN] slow")]
+fn stderr_max_level_exceeded() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stderr", "INFO starting\n[WARN] slow\ndone")
+        .assert()
+        .stderr_max_level("info");
+}
+
+#[test]
+#[should_panic(expected = "Unexpected stdout, `aa` appears 2 times")]
+fn stdout_contains_count_non_overlapping() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "aaaaa")
+        .assert()
+        .stdout_contains_count("aa", 4);
+}
+
+#[test]
+#[cfg(unix)]
+fn exited_normally_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "1")
+        .assert()
+        .exited_normally();
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "Unexpected termination, killed by signal 15")]
+fn exited_normally_killed() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("kill -TERM $$")
+        .assert()
+        .exited_normally();
+}
+
+#[test]
+#[cfg(unix)]
+fn killed_by_signal_example() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("kill -TERM $$")
+        .assert()
+        .killed_by_signal(Some(libc::SIGTERM))
+        .killed_by_signal(None);
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "killed by signal 15, expected signal 9")]
+fn killed_by_signal_other() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("kill -TERM $$")
+        .assert()
+        .killed_by_signal(Some(libc::SIGKILL));
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "exited normally with code 3")]
+fn killed_by_signal_exited() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "3")
+        .assert()
+        .killed_by_signal(None);
+}
+
+#[test]
+fn with_line_width_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "aGVsbG8gd29ybGQ=")
+        .assert()
+        .with_line_width(80)
+        .success();
+}
+
+#[test]
+#[should_panic(expected = "stdout=```aGVsbG8g\u{2026}(+8 chars)\n```")]
+fn with_line_width_elides() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "aGVsbG8gd29ybGQ=")
+        .assert()
+        .with_line_width(8)
+        .failure();
+}
+
+#[test]
+fn failure_with_stderr_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "1")
+        .env("stderr", "error: invalid input")
+        .assert()
+        .failure_with_stderr();
+}
+
+#[test]
+#[should_panic(expected = "Unexplained failure, nothing written to stderr")]
+fn failure_with_stderr_silent() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "1")
+        .assert()
+        .failure_with_stderr();
+}
+
+#[test]
+#[should_panic(expected = "Unexpected success")]
+fn failure_with_stderr_success() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stderr", "warning")
+        .assert()
+        .failure_with_stderr();
+}
+
+#[test]
+fn indoc_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello\n  world")
+        .env("stderr", "error")
+        .assert()
+        .stdout_indoc(
+            "
+            hello
+              world
+            ",
+        )
+        .stderr_indoc(
+            "
+            error
+            ",
+        );
+}
+
+#[test]
+fn one_of_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hi")
+        .env("stderr", "world")
+        .assert()
+        .stdout_one_of(&["hello\n", "hi\n"])
+        .stderr_one_of(&["world\n"]);
+}
+
+#[test]
+#[should_panic(expected = "Unexpected stdout, matched none of:\n- ```hello\n```\n- ```hi\n```")]
+fn stdout_one_of_none() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hey")
+        .assert()
+        .stdout_one_of(&["hello\n", "hi\n"]);
+}
+
+#[test]
+fn on_success_stdout_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .assert()
+        .on_success_stdout("hello\n")
+        .on_failure_stderr("error\n");
+}
+
+#[test]
+fn on_failure_stderr_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "1")
+        .env("stderr", "error")
+        .assert()
+        .on_success_stdout("hello\n")
+        .on_failure_stderr("error\n");
+}
+
+#[test]
+#[should_panic(expected = "Unexpected stdout")]
+fn on_success_stdout_mismatch() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "goodbye")
+        .assert()
+        .on_success_stdout("hello\n");
+}
+
+#[test]
+#[cfg(unix)]
+fn output_file_example() {
+    let path = env::temp_dir().join(format!("assert_cmd-output_file-{}.txt", process::id()));
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo hello > \"$0\"")
+        .arg(&path)
+        .assert()
+        .success()
+        .output_file(&path, "hello\n");
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+#[should_panic(expected = "Unexpected output file, failed to read")]
+fn output_file_missing() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .assert()
+        .output_file("tests/fixtures/missing.txt", "hello\n");
+}
+
+#[test]
+#[should_panic(expected = "Unexpected output file `tests/fixtures/hello.txt`, failed")]
+fn output_file_mismatch() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .assert()
+        .output_file("tests/fixtures/hello.txt", "world\n");
+}
+
+#[test]
+fn silent_example() {
+    Command::cargo_bin("bin_fixture").unwrap().assert().silent();
+}
+
+#[test]
+#[should_panic(expected = "Unexpected output on stderr (6 bytes)")]
+fn silent_stderr() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stderr", "world")
+        .assert()
+        .silent();
+}
+
+fn parse_number(stdout: &[u8]) -> Result<u32, String> {
+    std::str::from_utf8(stdout)
+        .map_err(|e| e.to_string())
+        .and_then(|s| s.trim().parse::<u32>().map_err(|e| e.to_string()))
+}
+
+#[test]
+fn stdout_parses_with_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "42")
+        .assert()
+        .stdout_parses_with(parse_number);
+}
+
+#[test]
+#[should_panic(expected = "Unexpected stdout, failed to parse: invalid digit found in string")]
+fn stdout_parses_with_invalid() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "forty-two")
+        .assert()
+        .stdout_parses_with(parse_number);
+}
+
+#[test]
+fn code_matches_example() {
+    let baseline = Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "42")
+        .assert();
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "42")
+        .assert()
+        .code_matches(&baseline);
+}
+
+#[test]
+#[should_panic(expected = "Unexpected return code 0, expected 42 from the other run")]
+fn code_matches_differs() {
+    let baseline = Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "42")
+        .assert();
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .assert()
+        .code_matches(&baseline);
+}
+
+#[test]
+fn code_matches_any_example() {
+    let found = Command::cargo_bin("bin_fixture").unwrap().assert();
+    let missing = Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "1")
+        .assert();
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "1")
+        .assert()
+        .code_matches_any(&[&found, &missing]);
+}
+
+#[test]
+#[should_panic(expected = "Unexpected return code 3, expected any of [0, 1] from the other runs")]
+fn code_matches_any_differs() {
+    let found = Command::cargo_bin("bin_fixture").unwrap().assert();
+    let missing = Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "1")
+        .assert();
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "3")
+        .assert()
+        .code_matches_any(&[&found, &missing]);
+}
+
+#[test]
+fn code_shell_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "42")
+        .assert()
+        .code_shell(42);
+}
+
+#[test]
+#[should_panic(expected = "Unexpected return code 44 (raw ")]
+fn code_shell_mismatch() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "300")
+        .assert()
+        .code_shell(42);
+}
+
+#[test]
+#[should_panic(expected = "Invalid expected code 298, a shell only sees 0 to 255")]
+fn code_shell_unreachable() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "42")
+        .assert()
+        .code_shell(298);
+}
+
+#[test]
+fn is_ascii_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello\tworld")
+        .env("stderr", "hello\r")
+        .assert()
+        .stdout_is_ascii()
+        .stderr_is_ascii_allowing(b"\r\n");
+}
+
+#[test]
+#[should_panic(expected = "Unexpected stdout, disallowed byte 0x1b at offset 0")]
+fn stdout_is_ascii_color() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "\x1b[31mhello\x1b[0m")
+        .assert()
+        .stdout_is_ascii();
+}
+
+#[test]
+fn soft_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .env("stderr", "world")
+        .assert()
+        .soft()
+        .code(0)
+        .stdout("hello\n")
+        .stderr("world\n")
+        .report();
+}
+
+#[test]
+fn soft_collects_failures() {
+    let err = Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .env("stderr", "world")
+        .assert()
+        .soft()
+        .failure()
+        .code(1)
+        .stdout("hello\n")
+        .stderr("goodbye\n")
+        .try_report()
+        .unwrap_err();
+    assert_eq!(err.failures().len(), 3);
+}
+
+#[test]
+fn stdout_eq_any_path_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .assert()
+        .stdout_eq_any_path(&["tests/fixtures/world.txt", "tests/fixtures/hello.txt"]);
+}
+
+#[test]
+#[should_panic(expected = "closest is tests/fixtures/hello_world.txt")]
+fn stdout_eq_any_path_closest() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello\nworld\n")
+        .assert()
+        .stdout_eq_any_path(&["tests/fixtures/world.txt", "tests/fixtures/hello_world.txt"]);
+}
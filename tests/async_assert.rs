@@ -0,0 +1,28 @@
+#![cfg(feature = "tokio")]
+
+extern crate assert_cmd;
+extern crate tokio;
+
+use assert_cmd::prelude::*;
+
+fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn assert_example() {
+    let mut cmd = tokio::process::Command::new(assert_cmd::cargo::cargo_bin("bin_fixture"));
+    cmd.env("stdout", "hello");
+    runtime().block_on(cmd.assert()).success().stdout("hello\n");
+}
+
+#[test]
+#[should_panic(expected = "program=`")]
+fn assert_context() {
+    let mut cmd = tokio::process::Command::new(assert_cmd::cargo::cargo_bin("bin_fixture"));
+    cmd.env("exit", "1");
+    runtime().block_on(cmd.assert()).success();
+}
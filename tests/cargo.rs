@@ -2,6 +2,7 @@ extern crate assert_cmd;
 extern crate escargot;
 extern crate predicates;
 
+use std::env;
 use std::process;
 use std::process::Command;
 
@@ -21,6 +22,16 @@ fn cargo_binary_with_empty_env() {
     cmd.assert().success().stdout("42\n");
 }
 
+#[test]
+fn cargo_binary_with_current_dir() {
+    let path = assert_cmd::cargo::cargo_bin("bin_fixture");
+    assert!(path.is_absolute(), "{} is relative", path.display());
+
+    let mut cmd = process::Command::cargo_bin("bin_fixture").unwrap();
+    cmd.current_dir(env::temp_dir()).env("stdout", "42");
+    cmd.assert().success().stdout("42\n");
+}
+
 #[test]
 fn mod_example() {
     let bin_under_test = escargot::CargoBuild::new()
@@ -56,3 +67,18 @@ fn cargo_bin_example_2() {
     let output = cmd.unwrap();
     println!("{:?}", output);
 }
+
+#[test]
+fn cargo_bin_with_args_example() {
+    let output = Command::cargo_bin_with_args("bin_fixture", ["--flag", "v"])
+        .unwrap()
+        .unwrap();
+    println!("{:?}", output);
+}
+
+#[test]
+fn cargo_bin_with_args_sets_args() {
+    let cmd = Command::cargo_bin_with_args("bin_fixture", vec!["--flag", "v"]).unwrap();
+    let args: Vec<_> = cmd.get_args().collect();
+    assert_eq!(args, ["--flag", "v"]);
+}
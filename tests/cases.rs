@@ -0,0 +1,34 @@
+#[macro_use]
+extern crate assert_cmd;
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+
+#[test]
+fn cases_example() {
+    assert_cmd_cases! {
+        Command::cargo_bin("bin_fixture").unwrap();
+        case("default", code: 0, stdout: ""),
+        case("hello", env: { "stdout" => "hello" }, code: 0, stdout: "hello\n"),
+        case("fail", env: { "exit" => "42", "stderr" => "oops" }, code: 42, stderr: "oops\n"),
+    }
+}
+
+#[test]
+fn cases_args() {
+    assert_cmd_cases! {
+        Command::new("echo");
+        case("args", args: ["hello", "world"], stdout: "hello world\n"),
+    }
+}
+
+#[test]
+#[should_panic(expected = "case=`mismatch`")]
+fn cases_failure_names_case() {
+    assert_cmd_cases! {
+        Command::cargo_bin("bin_fixture").unwrap();
+        case("match", code: 0),
+        case("mismatch", env: { "exit" => "1" }, code: 0),
+    }
+}
@@ -0,0 +1,47 @@
+#![cfg(feature = "csv")]
+
+extern crate assert_cmd;
+extern crate predicates;
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn stdout_csv_headers_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "name,size\nhello.txt,5")
+        .assert()
+        .stdout_csv_headers(&["name", "size"]);
+}
+
+#[test]
+#[should_panic(expected = "CSV headers are [\"name\", \"size\"], expected [\"name\"]")]
+fn stdout_csv_headers_mismatch() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "name,size\nhello.txt,5")
+        .assert()
+        .stdout_csv_headers(&["name"]);
+}
+
+#[test]
+fn stdout_csv_rows_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "name,size\nhello.txt,5\nworld.txt,5")
+        .assert()
+        .stdout_csv_rows(predicate::eq(2));
+}
+
+#[test]
+#[should_panic(expected = "line: 3")]
+fn stdout_csv_rows_ragged() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "name,size\nhello.txt,5\nworld.txt")
+        .assert()
+        .stdout_csv_rows(predicate::eq(2));
+}
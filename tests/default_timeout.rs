@@ -0,0 +1,99 @@
+extern crate assert_cmd;
+
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+use assert_cmd::prelude::*;
+use assert_cmd::run::LineAction;
+
+#[test]
+#[should_panic(expected = "Command timed out after 200ms, killed")]
+fn with_default_timeout_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("sleep", "5000")
+        .with_default_timeout(Duration::from_millis(200))
+        .assert();
+}
+
+#[test]
+#[should_panic(expected = "Command timed out after 200ms, killed")]
+fn with_default_timeout_later_command() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .with_default_timeout(Duration::from_millis(200))
+        .assert()
+        .success();
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("sleep", "5000")
+        .assert();
+}
+
+#[test]
+#[should_panic(expected = "Command timed out after 200ms, killed")]
+fn set_default_timeout_stdin() {
+    assert_cmd::run::set_default_timeout(Some(Duration::from_millis(200)));
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("sleep", "5000")
+        .with_stdin()
+        .buffer("42")
+        .assert();
+}
+
+#[test]
+#[should_panic(expected = "Command timed out after 200ms, killed")]
+fn set_default_timeout_concurrent() {
+    assert_cmd::run::set_default_timeout(Some(Duration::from_millis(200)));
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("sleep", "5000")
+        .assert_concurrent(2);
+}
+
+#[test]
+#[should_panic(expected = "Command timed out after 200ms, killed")]
+fn set_default_timeout_stable_size() {
+    assert_cmd::run::set_default_timeout(Some(Duration::from_millis(200)));
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("sleep", "5000")
+        .assert_stable_size(2);
+}
+
+#[test]
+#[should_panic(expected = "Command timed out after 200ms, killed")]
+fn set_default_timeout_stdout_lines() {
+    assert_cmd::run::set_default_timeout(Some(Duration::from_millis(200)));
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("sleep", "5000")
+        .for_each_stdout_line(|_| LineAction::Continue);
+}
+
+#[test]
+fn set_default_timeout_cleared() {
+    assert_cmd::run::set_default_timeout(Some(Duration::from_millis(200)));
+    assert_cmd::run::set_default_timeout(None);
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("sleep", "500")
+        .assert()
+        .success();
+}
+
+#[test]
+fn set_default_timeout_thread_local() {
+    assert_cmd::run::set_default_timeout(Some(Duration::from_millis(200)));
+    thread::spawn(|| {
+        Command::cargo_bin("bin_fixture")
+            .unwrap()
+            .env("sleep", "500")
+            .assert()
+            .success();
+    })
+    .join()
+    .unwrap();
+}
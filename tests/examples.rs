@@ -20,3 +20,11 @@ fn lib_example() {
     let assert = cmd.assert();
     assert.failure().code(42).stdout("hello\n");
 }
+
+#[test]
+fn cases_by_path() {
+    assert_cmd::assert_cmd_cases! {
+        Command::cargo_bin("bin_fixture").unwrap();
+        case("hello", env: { "stdout" => "hello" }, args: ["-A"], code: 0, stdout: "hello\n"),
+    }
+}
@@ -0,0 +1,90 @@
+extern crate assert_cmd;
+
+use std::process;
+use std::process::Command;
+use std::time::Duration;
+
+use assert_cmd::cargo::cargo_bin;
+use assert_cmd::fixture;
+use assert_cmd::fixture::Fixture;
+use assert_cmd::prelude::*;
+
+#[test]
+fn fixture_main() {
+    if !fixture::is_fixture() {
+        return;
+    }
+    println!("hello");
+    eprintln!("world");
+    process::exit(3);
+}
+
+#[test]
+fn run_fixture_main() {
+    fixture::run_fixture();
+}
+
+#[test]
+fn current_exe_as_fixture_example() {
+    Command::current_exe_as_fixture()
+        .unwrap()
+        .args(["--exact", "fixture_main", "--nocapture"])
+        .assert()
+        .code(3)
+        .stdout_ends_with("hello\n")
+        .stderr("world\n");
+}
+
+#[test]
+fn is_fixture_unset() {
+    assert!(!fixture::is_fixture());
+}
+
+#[test]
+fn fixture_example() {
+    Fixture::new()
+        .stdout("hi")
+        .stderr("err")
+        .exit(2)
+        .current_exe()
+        .unwrap()
+        .args(["--exact", "run_fixture_main", "--nocapture"])
+        .assert()
+        .code(2)
+        .stdout_ends_with("hi\n")
+        .stderr("err\n");
+}
+
+#[test]
+fn fixture_command_for() {
+    Fixture::new()
+        .stdout("hi")
+        .stderr("err")
+        .exit(2)
+        .command_for(cargo_bin("bin_fixture"))
+        .assert()
+        .code(2)
+        .stdout("hi\n")
+        .stderr("err\n");
+}
+
+#[test]
+fn fixture_default() {
+    Fixture::new()
+        .command_for(cargo_bin("bin_fixture"))
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("");
+}
+
+#[test]
+fn fixture_sleep() {
+    Fixture::new()
+        .sleep(Duration::from_millis(10))
+        .current_exe()
+        .unwrap()
+        .args(["--exact", "run_fixture_main", "--nocapture"])
+        .assert()
+        .success();
+}
@@ -0,0 +1,20 @@
+#![cfg(feature = "insta")]
+
+#[macro_use]
+extern crate assert_cmd;
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+
+#[test]
+fn assert_cmd_snapshot_example() {
+    let assert = Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .env("stderr", "took 42ms")
+        .assert()
+        .success()
+        .snapshot_filter(r"\d+ms", "[DURATION]");
+    assert_cmd_snapshot!("hello", assert);
+}
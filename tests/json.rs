@@ -0,0 +1,47 @@
+#![cfg(feature = "json")]
+
+extern crate assert_cmd;
+#[macro_use]
+extern crate serde_json;
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+
+#[test]
+fn stdout_json_field_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", r#"{"version": "1.2.3"}"#)
+        .assert()
+        .stdout_json_field(".version", json!("1.2.3"));
+}
+
+#[test]
+fn stdout_json_field_nested() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", r#"{"items": [{"id": 1}, {"id": 2}]}"#)
+        .assert()
+        .stdout_json_field("$.items[1].id", json!(2));
+}
+
+#[test]
+#[should_panic(expected = "JSON field `.name` is not present, expected \"bin\"")]
+fn stdout_json_field_missing() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", r#"{"version": "1.2.3"}"#)
+        .assert()
+        .stdout_json_field(".name", json!("bin"));
+}
+
+#[test]
+#[should_panic(expected = "JSON field `.version` is \"1.2.4\", expected \"1.2.3\"")]
+fn stdout_json_field_mismatch() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", r#"{"version": "1.2.4"}"#)
+        .assert()
+        .stdout_json_field(".version", json!("1.2.3"));
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "jsonschema")]
+
+extern crate assert_cmd;
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+
+const SCHEMA: &str = r#"{
+    "type": "object",
+    "properties": {"items": {"type": "array", "items": {"type": "string"}}},
+    "required": ["version"]
+}"#;
+
+#[test]
+fn stdout_json_schema_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", r#"{"version": "1.2.3", "items": ["a"]}"#)
+        .assert()
+        .stdout_json_schema(SCHEMA);
+}
+
+#[test]
+#[should_panic(expected = "/items/1: 2 is not of type \"string\"")]
+fn stdout_json_schema_invalid_item() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", r#"{"version": "1.2.3", "items": ["a", 2]}"#)
+        .assert()
+        .stdout_json_schema(SCHEMA);
+}
+
+#[test]
+#[should_panic(expected = "/: \"version\" is a required property")]
+fn stdout_json_schema_missing_property() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "{}")
+        .assert()
+        .stdout_json_schema(SCHEMA);
+}
+
+#[test]
+#[should_panic(expected = "invalid JSON")]
+fn stdout_json_schema_not_json() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .assert()
+        .stdout_json_schema(SCHEMA);
+}
+
+#[test]
+fn json_schema_predicate() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", r#"{"version": "1.2.3"}"#)
+        .assert()
+        .stdout(assert_cmd::predicate::json_schema(SCHEMA));
+}
@@ -0,0 +1,50 @@
+extern crate assert_cmd;
+extern crate predicates;
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use predicates::BoxPredicate;
+
+#[test]
+fn lines_eq_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .assert()
+        .stdout(assert_cmd::predicate::lines_eq("hello\n"));
+}
+
+#[test]
+#[should_panic(expected = "@@ -1,1 +1,1 @@")]
+fn lines_eq_diff() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .assert()
+        .stdout(assert_cmd::predicate::lines_eq("world\n"));
+}
+
+#[test]
+fn named_example() {
+    let greeting = BoxPredicate::new(predicate::str::contains("hello").from_utf8());
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .assert()
+        .stdout(assert_cmd::predicate::named("greeting", greeting));
+}
+
+#[test]
+#[should_panic(expected = "failed farewell: var.contains(\"goodbye\")")]
+fn named_failure() {
+    let greeting = BoxPredicate::new(predicate::str::contains("hello").from_utf8());
+    let farewell = BoxPredicate::new(predicate::str::contains("goodbye").from_utf8());
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .assert()
+        .stdout(assert_cmd::predicate::named("greeting", greeting))
+        .stdout(assert_cmd::predicate::named("farewell", farewell));
+}
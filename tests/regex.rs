@@ -0,0 +1,73 @@
+#![cfg(feature = "regex")]
+
+extern crate assert_cmd;
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+
+#[test]
+fn ignoring_prefix_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "[12:00:01] INFO hello")
+        .env("stderr", "[12:00:01] WARN world")
+        .assert()
+        .stdout_ignoring_prefix(r"\[[0-9:]+\] \w+ ", "hello\n")
+        .stderr_ignoring_prefix(r"\[[0-9:]+\] \w+ ", "world\n");
+}
+
+#[test]
+#[should_panic(expected = "stripped: goodbye")]
+fn stdout_ignoring_prefix_mismatch() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "[12:00:01] INFO goodbye")
+        .assert()
+        .stdout_ignoring_prefix(r"\[[0-9:]+\] \w+ ", "hello\n");
+}
+
+#[test]
+fn stdout_normalizing_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "wrote /tmp/.tmpA1b2C3/out.txt")
+        .assert()
+        .stdout_normalizing(&[(r"/tmp/\.tmp\w+", "<TMP>")], "wrote <TMP>/out.txt\n");
+}
+
+#[test]
+fn stderr_normalizing_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stderr", "wrote /tmp/.tmpA1b2C3/out.txt")
+        .assert()
+        .stderr_normalizing(&[(r"/tmp/\.tmp\w+", "<TMP>")], "wrote <TMP>/out.txt\n");
+}
+
+#[test]
+#[should_panic(expected = "normalized=```wrote <TMP>/out.txt\n```")]
+fn stdout_normalizing_mismatch() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "wrote /tmp/.tmpA1b2C3/out.txt")
+        .assert()
+        .stdout_normalizing(&[(r"/tmp/\.tmp\w+", "<TMP>")], "wrote <TMP>/in.txt\n");
+}
+
+#[test]
+fn snapshot_filter_example() {
+    let snapshot = Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "took 42ms")
+        .env("stderr", "at /tmp/abc123\r")
+        .env("exit", "1")
+        .assert()
+        .snapshot_filter(r"\d+ms", "[DURATION]")
+        .snapshot_filter(r"/tmp/\w+", "[TEMP]")
+        .to_snapshot();
+    assert_eq!(
+        snapshot,
+        "code: 1\nstdout:\ntook [DURATION]\nstderr:\nat [TEMP]\n"
+    );
+}
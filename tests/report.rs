@@ -0,0 +1,50 @@
+#![cfg(feature = "serde")]
+
+extern crate assert_cmd;
+#[cfg(feature = "json")]
+extern crate serde_json;
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+
+#[test]
+fn to_report_example() {
+    let report = Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .assert()
+        .to_report();
+    assert!(report.success);
+    assert_eq!(report.code, Some(0));
+    assert_eq!(report.stdout.text, "hello\n");
+    assert_eq!(report.stdout.bytes, None);
+    assert_eq!(report.context[0].name, "command");
+}
+
+#[test]
+fn to_report_non_utf8() {
+    let report = std::process::Output {
+        status: Command::cargo_bin("bin_fixture").unwrap().status().unwrap(),
+        stdout: vec![b'a', 0xff],
+        stderr: vec![],
+    }
+    .assert()
+    .to_report();
+    assert_eq!(report.stdout.text, "a\u{fffd}");
+    assert_eq!(report.stdout.bytes, Some(vec![b'a', 0xff]));
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn to_report_serialize() {
+    let report = Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "1")
+        .assert()
+        .to_report();
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["code"], 1);
+    assert_eq!(json["success"], false);
+    assert_eq!(json["stderr"]["text"], "");
+}
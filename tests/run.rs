@@ -0,0 +1,832 @@
+extern crate assert_cmd;
+#[cfg(unix)]
+extern crate libc;
+
+use std::process::Command;
+use std::time::Duration;
+
+use assert_cmd::prelude::*;
+use assert_cmd::run::LineAction;
+
+#[test]
+fn does_not_leak_env_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .assert_does_not_leak_env("API_TOKEN")
+        .success();
+}
+
+#[test]
+#[should_panic(expected = "Leaked `stdout`")]
+fn does_not_leak_env_leaked() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .assert_does_not_leak_env("stdout");
+}
+
+#[test]
+#[cfg(unix)]
+fn send_signal_then_assert_example() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("trap 'echo shutting down; exit 0' TERM; sleep 5 > /dev/null & wait")
+        .send_signal_then_assert(libc::SIGTERM)
+        .success()
+        .stdout("shutting down\n");
+}
+
+#[test]
+#[cfg(unix)]
+fn send_signal_then_assert_killed() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("sleep", "5000")
+        .send_signal_then_assert(libc::SIGTERM)
+        .interrupted();
+}
+
+#[test]
+fn assert_within_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .assert_within(Duration::from_secs(30))
+        .success();
+}
+
+#[test]
+#[should_panic(expected = "Command timed out after 200ms, killed")]
+fn assert_within_timeout() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("sleep", "5000")
+        .assert_within(Duration::from_millis(200));
+}
+
+#[test]
+#[cfg(unix)]
+fn terminates_within_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("sleep", "5000")
+        .terminates_within(libc::SIGTERM, Duration::from_secs(1))
+        .interrupted();
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "Command still running")]
+fn terminates_within_ignored() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("trap '' TERM; exec sleep 5")
+        .terminates_within(libc::SIGTERM, Duration::from_millis(200));
+}
+
+#[test]
+#[cfg(unix)]
+fn stdin_eof_then_assert_example() {
+    Command::new("cat")
+        .stdin_eof_then_assert("hello", Duration::from_secs(1))
+        .stdout("hello");
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "Command still running 200ms after stdin closed, killed")]
+fn stdin_eof_then_assert_hangs() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("cat >/dev/null; exec sleep 5")
+        .stdin_eof_then_assert("hello", Duration::from_millis(200));
+}
+
+#[test]
+fn assert_matches_command_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .assert_matches_command(Command::new("echo").arg("hello"))
+        .success();
+}
+
+#[test]
+#[should_panic(expected = "stdout differs, from reference to command")]
+fn assert_matches_command_differs() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .assert_matches_command(Command::new("echo").arg("world"));
+}
+
+#[test]
+#[should_panic(expected = "code differs: reference Some(0), command Some(1)")]
+fn assert_matches_command_exactly_code() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .env("exit", "1")
+        .assert_matches_command_exactly(Command::new("echo").arg("hello"));
+}
+
+#[test]
+fn assert_quieter_than_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .assert_quieter_than(
+            Command::cargo_bin("bin_fixture")
+                .unwrap()
+                .env("stdout", "hello")
+                .env("stderr", "progress"),
+        )
+        .success();
+}
+
+#[test]
+#[should_panic(
+    expected = "Unexpected output size, 6 bytes in total, expected fewer than the baseline's 6 bytes"
+)]
+fn assert_quieter_than_same() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .assert_quieter_than(
+            Command::cargo_bin("bin_fixture")
+                .unwrap()
+                .env("stdout", "hello"),
+        );
+}
+
+#[test]
+#[cfg(unix)]
+fn assert_format_idempotent_example() {
+    Command::new("tr")
+        .args(["a-z", "A-Z"])
+        .assert_format_idempotent(b"hello")
+        .stdout("HELLO");
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "Unexpected reformatting, from first to second pass")]
+fn assert_format_idempotent_unstable() {
+    Command::new("sed")
+        .arg("s/^/> /")
+        .assert_format_idempotent(b"hello\n");
+}
+
+#[test]
+fn assert_matches_command_stdin() {
+    Command::new("cat")
+        .with_stdin()
+        .buffer("42")
+        .assert_matches_command_exactly(&mut Command::new("cat"))
+        .stdout("42");
+}
+
+#[test]
+#[cfg(unix)]
+fn assert_matches_shell_example() {
+    Command::new("/bin/echo")
+        .args(["hello", "world"])
+        .assert_matches_shell("echo \"$@\"")
+        .stdout("hello world\n");
+    Command::new("false").assert_matches_shell("false");
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "code differs: reference Some(3), command Some(0)")]
+fn assert_matches_shell_code() {
+    Command::new("true").assert_matches_shell("exit 3");
+}
+
+#[test]
+#[cfg(unix)]
+fn assert_matches_shell_stdin() {
+    Command::new("cat")
+        .with_stdin()
+        .buffer("42")
+        .assert_matches_shell("cat")
+        .stdout("42");
+}
+
+#[test]
+#[cfg(unix)]
+fn assert_with_fds_example() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo ready >&3; echo done >&4; echo hello")
+        .assert_with_fds(&[3, 4])
+        .success()
+        .stdout("hello\n")
+        .fd(3, "ready\n")
+        .fd(4, "done\n");
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "Unexpected fd 3")]
+fn assert_with_fds_mismatch() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo ready >&3")
+        .assert_with_fds(&[3])
+        .fd(3, "done\n");
+}
+
+#[test]
+#[cfg(unix)]
+fn assert_with_fds_rerun() {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg("echo ready >&3");
+    assert_eq!(cmd.assert_with_fds(&[3]).get_extra_fd(3), b"ready\n");
+    assert_eq!(cmd.assert_with_fds(&[3]).get_extra_fd(3), b"ready\n");
+    // The fd isn't connected outside of `assert_with_fds`.
+    cmd.assert().failure();
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "Invalid fd 1, only fds after stderr (2) can be collected")]
+fn assert_with_fds_stdout() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo ready")
+        .assert_with_fds(&[1]);
+}
+
+#[test]
+#[cfg(unix)]
+fn assert_with_max_rss_example() {
+    let assert = Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .assert_with_max_rss()
+        .success()
+        .stdout("hello\n");
+    assert!(0 < assert.max_rss());
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "Unexpected memory use")]
+fn assert_with_max_rss_above() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .assert_with_max_rss()
+        .max_rss_below(1024);
+}
+
+#[test]
+#[cfg(unix)]
+fn assert_combined_example() {
+    let assert = Command::new("sh")
+        .arg("-c")
+        .arg("echo starting >&2; echo result; echo done >&2")
+        .assert_combined()
+        .success()
+        .stdout("")
+        .combined_order(&["starting", "result", "done"]);
+    assert_eq!(assert.get_combined(), b"starting\nresult\ndone\n");
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "found `result` at 9, `starting` at 0, `done` missing")]
+fn assert_combined_out_of_order() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo starting >&2; echo result")
+        .assert_combined()
+        .combined_order(&["result", "starting", "done"]);
+}
+
+#[test]
+#[should_panic(expected = "Output not captured combined")]
+fn combined_order_not_captured() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .assert()
+        .combined_order(&["hello"]);
+}
+
+#[test]
+#[cfg(unix)]
+fn assert_with_process_group_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .assert_with_process_group()
+        .success()
+        .no_orphans();
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "Unexpected processes left running in process group")]
+fn assert_with_process_group_orphan() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("sleep 5 > /dev/null 2>&1 &")
+        .assert_with_process_group()
+        .success()
+        .no_orphans();
+}
+
+#[test]
+#[cfg(unix)]
+fn assert_with_process_group_rerun() {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c")
+        .arg(r#"test "$(ps -o pgid= -p $$ | tr -d ' ')" = $$"#);
+    cmd.assert_with_process_group().success();
+    // The command only leads its own process group under `assert_with_process_group`.
+    cmd.assert().failure();
+}
+
+#[test]
+fn assert_locale_stable_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "1.5")
+        .assert_locale_stable(&["C", "de_DE.UTF-8", "fr_FR.UTF-8"])
+        .success()
+        .stdout("1.5\n");
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "LC_ALL=POSIX: stdout differs")]
+fn assert_locale_stable_dependent() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo $LC_ALL")
+        .assert_locale_stable(&["C", "POSIX"]);
+}
+
+#[test]
+#[cfg(unix)]
+fn assert_locale_stable_restores_env() {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg("echo ${LC_ALL+set}").env_remove("LC_ALL");
+    cmd.assert_locale_stable(&["C", "POSIX"]).stdout("set\n");
+    cmd.assert().stdout("\n");
+}
+
+#[test]
+#[cfg(all(unix, feature = "tempfile"))]
+fn with_config_file_example() {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c")
+        .arg("test \"$1\" = --config && cat \"$2\"")
+        .arg("sh");
+    let (cmd, config) = cmd.with_config_file("verbose = true").unwrap();
+    cmd.assert().success().stdout("verbose = true");
+    assert!(config.exists());
+}
+
+#[test]
+#[cfg(all(unix, feature = "tempfile"))]
+#[should_panic(expected = "config=`")]
+fn with_config_file_context() {
+    let mut cmd = Command::new("false");
+    let (cmd, _config) = cmd.with_config_file("verbose = true").unwrap();
+    cmd.assert().success();
+}
+
+#[test]
+fn assert_help_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "Usage: bin_fixture [OPTIONS]")
+        .assert_help();
+}
+
+#[test]
+#[should_panic(expected = "`--help` printed nothing")]
+fn assert_help_empty() {
+    Command::cargo_bin("bin_fixture").unwrap().assert_help();
+}
+
+#[test]
+fn assert_version_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "bin_fixture 1.2.3")
+        .assert_version("1.2.3");
+}
+
+#[test]
+#[should_panic(expected = "no version number")]
+fn assert_version_missing() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "bin_fixture")
+        .assert_version("bin_fixture");
+}
+
+#[test]
+#[should_panic(expected = "missing version `2.0.0`")]
+fn assert_version_mismatch() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "bin_fixture 1.2.3")
+        .assert_version("2.0.0");
+}
+
+#[cfg(unix)]
+fn scratch_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("assert_cmd-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+#[cfg(unix)]
+fn assert_dash_is_stdout_example() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("if [ \"$2\" = - ]; then echo converted; else echo converted >\"$2\"; fi")
+        .arg("sh")
+        .current_dir(scratch_dir("dash_stdout"))
+        .assert_dash_is_stdout("--output", "converted\n")
+        .success();
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "expected `-` to mean stdout")]
+fn assert_dash_is_stdout_file() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo converted >\"$2\"")
+        .arg("sh")
+        .current_dir(scratch_dir("dash_file"))
+        .assert_dash_is_stdout("--output", "converted\n");
+}
+
+#[test]
+#[cfg(unix)]
+fn assert_no_writes_outside_example() {
+    let cwd = scratch_dir("no_writes_cwd");
+    let allowed = scratch_dir("no_writes_allowed");
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo hello > \"$0/out.txt\"")
+        .arg(&allowed)
+        .current_dir(&cwd)
+        .assert_no_writes_outside(&allowed)
+        .success();
+    std::fs::remove_dir_all(&cwd).unwrap();
+    std::fs::remove_dir_all(&allowed).unwrap();
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "leaked.txt")]
+fn assert_no_writes_outside_cwd() {
+    let cwd = scratch_dir("no_writes_leak");
+    let allowed = cwd.join("allowed");
+    std::fs::create_dir_all(&allowed).unwrap();
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo hello > leaked.txt")
+        .current_dir(&cwd)
+        .assert_no_writes_outside(&allowed);
+}
+
+#[test]
+#[cfg(unix)]
+fn assert_dry_run_clean_example() {
+    let watched = scratch_dir("dry_run_clean");
+    std::fs::create_dir_all(watched.join("sub")).unwrap();
+    std::fs::write(watched.join("sub/kept.txt"), "hello").unwrap();
+    Command::new("sh")
+        .arg("-c")
+        .arg("cat sub/kept.txt")
+        .current_dir(&watched)
+        .assert_dry_run_clean(&watched)
+        .success()
+        .stdout("hello");
+    std::fs::remove_dir_all(&watched).unwrap();
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "added")]
+fn assert_dry_run_clean_added() {
+    let watched = scratch_dir("dry_run_added");
+    std::fs::create_dir_all(watched.join("sub")).unwrap();
+    let _ = std::fs::remove_file(watched.join("sub/new.txt"));
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo hello > sub/new.txt")
+        .current_dir(&watched)
+        .assert_dry_run_clean(&watched);
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "modified")]
+fn assert_dry_run_clean_modified() {
+    let watched = scratch_dir("dry_run_modified");
+    std::fs::write(watched.join("kept.txt"), "hello").unwrap();
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo world >> kept.txt")
+        .current_dir(&watched)
+        .assert_dry_run_clean(&watched);
+}
+
+#[test]
+#[cfg(unix)]
+fn assert_atomic_write_example() {
+    let dir = scratch_dir("atomic_write");
+    // The command crashes itself once done writing, rather than racing the signal.
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo hello > .out.txt.tmp && mv .out.txt.tmp out.txt && kill -KILL $$")
+        .current_dir(&dir)
+        .assert_atomic_write(
+            std::path::Path::new("out.txt"),
+            libc::SIGKILL,
+            Duration::from_secs(30),
+        )
+        .interrupted();
+    assert_eq!(
+        std::fs::read_to_string(dir.join("out.txt")).unwrap(),
+        "hello\n"
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "leftover")]
+fn assert_atomic_write_partial() {
+    let dir = scratch_dir("atomic_write_partial");
+    let _ = std::fs::remove_file(dir.join(".out.txt.tmp"));
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo hello > .out.txt.tmp && kill -KILL $$")
+        .current_dir(&dir)
+        .assert_atomic_write(
+            std::path::Path::new("out.txt"),
+            libc::SIGKILL,
+            Duration::from_secs(30),
+        );
+}
+
+#[test]
+fn assert_concurrent_example() {
+    let asserts = Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .assert_concurrent(4);
+    assert_eq!(asserts.len(), 4);
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "copy 1: stdout differs")]
+fn assert_concurrent_interference() {
+    let dir = scratch_dir("concurrent");
+    let _ = std::fs::remove_dir(dir.join("lock"));
+    // Only the first copy to create the lock directory claims it.
+    Command::new("sh")
+        .arg("-c")
+        .arg("mkdir \"$0/lock\" 2>/dev/null && echo claimed; true")
+        .arg(&dir)
+        .assert_concurrent(2);
+}
+
+#[test]
+#[should_panic(expected = "color=`never`")]
+fn no_color_context() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("CLICOLOR_FORCE", "1")
+        .no_color()
+        .assert()
+        .failure();
+}
+
+#[test]
+#[cfg(unix)]
+fn force_color_env() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo \"${NO_COLOR-unset} $CLICOLOR_FORCE\"")
+        .env("NO_COLOR", "1")
+        .force_color()
+        .assert()
+        .stdout("unset 1\n");
+}
+
+#[test]
+#[cfg(unix)]
+fn with_path_stub() {
+    let stubs = scratch_dir("with_path");
+    let stub = stubs.join("greet");
+    std::fs::write(&stub, "#!/bin/sh\necho stubbed\n").unwrap();
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&stub, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    Command::new("/bin/sh")
+        .arg("-c")
+        .arg("greet")
+        .with_path(&[&stubs])
+        .assert()
+        .stdout("stubbed\n");
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "PATH=`/nonexistent-a:/nonexistent-b`")]
+fn with_path_context() {
+    Command::new("/bin/sh")
+        .arg("-c")
+        .arg("greet")
+        .with_path(&[
+            std::path::Path::new("/nonexistent-a"),
+            std::path::Path::new("/nonexistent-b"),
+        ])
+        .assert()
+        .success();
+}
+
+#[test]
+#[cfg(unix)]
+fn prepend_path_keeps_existing() {
+    Command::new("/bin/sh")
+        .arg("-c")
+        .arg("echo \"$PATH\"")
+        .env("PATH", "/usr/bin")
+        .prepend_path(&[std::path::Path::new("/stubs")])
+        .assert()
+        .stdout("/stubs:/usr/bin\n");
+}
+
+#[test]
+fn completes_within_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .completes_within(Duration::from_secs(10))
+        .success();
+}
+
+#[test]
+#[should_panic(expected = "over its budget of 10ms")]
+fn completes_within_overrun() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("sleep", "200")
+        .env("stdout", "done")
+        .completes_within(Duration::from_millis(10));
+}
+
+#[test]
+fn assert_stable_size_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .assert_stable_size(3)
+        .success();
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "run 0: 2 bytes\nrun 1: 3 bytes")]
+fn assert_stable_size_unstable() {
+    let dir = scratch_dir("stable_size");
+    let _ = std::fs::remove_file(dir.join("count"));
+    // Each run appends to and prints a growing file.
+    Command::new("sh")
+        .arg("-c")
+        .arg("printf x >> \"$0/count\"; cat \"$0/count\"; echo")
+        .arg(&dir)
+        .assert_stable_size(2);
+}
+
+#[test]
+#[cfg(unix)]
+fn assert_reproducible_with_epoch_example() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo built at $SOURCE_DATE_EPOCH")
+        .assert_reproducible_with_epoch(1_700_000_000)
+        .stdout("built at 1700000000\n");
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "Unreproducible stdout, from first to second run")]
+fn assert_reproducible_with_epoch_clock() {
+    let dir = scratch_dir("reproducible");
+    let _ = std::fs::remove_file(dir.join("count"));
+    // Each run appends to and prints a growing file, like reading the clock.
+    Command::new("sh")
+        .arg("-c")
+        .arg("printf x >> \"$0/count\"; cat \"$0/count\"")
+        .arg(&dir)
+        .assert_reproducible_with_epoch(1_700_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Unexpected stdout, unchanged with SOURCE_DATE_EPOCH=1668377600")]
+fn assert_reproducible_with_epoch_ignored() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "hello")
+        .assert_reproducible_with_epoch(1_700_000_000);
+}
+
+#[test]
+#[cfg(unix)]
+fn assert_reproducible_with_epoch_restores_env() {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c")
+        .arg("echo built at $SOURCE_DATE_EPOCH")
+        .env("SOURCE_DATE_EPOCH", "42");
+    cmd.assert_reproducible_with_epoch(1_700_000_000);
+    cmd.assert().stdout("built at 42\n");
+}
+
+#[test]
+#[cfg(unix)]
+fn for_each_stdout_line_example() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo 'name?'; read name; echo \"hello $name\"")
+        .for_each_stdout_line(|line| match line {
+            "name?" => LineAction::WriteStdin("world\n".to_owned()),
+            _ => LineAction::Continue,
+        })
+        .success()
+        .stdout("name?\nhello world\n");
+}
+
+#[test]
+#[cfg(unix)]
+fn for_each_stdout_line_close_stdin() {
+    let mut lines = Vec::new();
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo ready; cat; echo done")
+        .for_each_stdout_line(|line| {
+            lines.push(line.to_owned());
+            match line {
+                "ready" => LineAction::WriteStdin("echoed\n".to_owned()),
+                "echoed" => LineAction::CloseStdin,
+                _ => LineAction::Continue,
+            }
+        })
+        .success();
+    assert_eq!(lines, ["ready", "echoed", "done"]);
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "Expected line `goodbye`, got `hello`")]
+fn for_each_stdout_line_expect() {
+    Command::new("sh")
+        .arg("-c")
+        .arg("echo first; echo hello; echo rest")
+        .for_each_stdout_line(|line| match line {
+            "first" => LineAction::Expect("goodbye".to_owned()),
+            _ => LineAction::Continue,
+        });
+}
+
+#[test]
+#[cfg(unix)]
+fn args_after_double_dash_example() {
+    Command::new("sh")
+        .args(["-c", "echo \"$@\"", "sh"])
+        .args_after_double_dash(&["-x", "--verbose"])
+        .assert()
+        .stdout("-- -x --verbose\n");
+}
+
+#[test]
+#[cfg(unix)]
+fn assert_args_after_double_dash_example() {
+    Command::new("sh")
+        .args(["-c", "echo \"$@\"", "sh"])
+        .assert_args_after_double_dash(&["-x", "--verbose"]);
+}
+
+#[test]
+#[cfg(unix)]
+#[should_panic(expected = "positional `-x` not echoed")]
+fn assert_args_after_double_dash_parsed() {
+    // Drops anything that looks like a flag.
+    Command::new("sh")
+        .args([
+            "-c",
+            "for a; do case $a in -*) ;; *) echo $a ;; esac; done",
+            "sh",
+        ])
+        .assert_args_after_double_dash(&["-x", "value"]);
+}
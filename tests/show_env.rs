@@ -0,0 +1,19 @@
+extern crate assert_cmd;
+
+use std::env;
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+
+// Kept in its own test binary since it changes the process environment.
+#[test]
+#[should_panic(expected = "env=`exit=\"1\" stdout=\"hello\"`")]
+fn show_env() {
+    env::set_var("ASSERT_CMD_SHOW_ENV", "1");
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("exit", "1")
+        .env("stdout", "hello")
+        .assert()
+        .success();
+}
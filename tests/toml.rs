@@ -0,0 +1,46 @@
+#![cfg(feature = "toml")]
+
+extern crate assert_cmd;
+extern crate toml;
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+
+#[test]
+fn stdout_toml_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "name   =   'bin'")
+        .assert()
+        .stdout_toml(toml::from_str("name = \"bin\"").unwrap());
+}
+
+#[test]
+fn stdout_toml_key_order() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "b = 2\na = 1")
+        .assert()
+        .stdout_toml(toml::from_str("a = 1\nb = 2").unwrap());
+}
+
+#[test]
+#[should_panic(expected = "+version = \"1.2.4\"")]
+fn stdout_toml_mismatch() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "version = '1.2.4'")
+        .assert()
+        .stdout_toml(toml::from_str("version = \"1.2.3\"").unwrap());
+}
+
+#[test]
+#[should_panic(expected = "invalid TOML")]
+fn stdout_toml_invalid() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "version = ")
+        .assert()
+        .stdout_toml(toml::from_str("version = \"1.2.3\"").unwrap());
+}
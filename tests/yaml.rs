@@ -0,0 +1,37 @@
+#![cfg(feature = "yaml")]
+
+extern crate assert_cmd;
+extern crate serde_yaml;
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+
+#[test]
+fn stdout_yaml_example() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "{name: 'bin'}")
+        .assert()
+        .stdout_yaml(serde_yaml::from_str("name: bin").unwrap());
+}
+
+#[test]
+#[should_panic(expected = "+version: 1.2.4")]
+fn stdout_yaml_mismatch() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "version: 1.2.4")
+        .assert()
+        .stdout_yaml(serde_yaml::from_str("version: 1.2.3").unwrap());
+}
+
+#[test]
+#[should_panic(expected = "invalid YAML")]
+fn stdout_yaml_invalid() {
+    Command::cargo_bin("bin_fixture")
+        .unwrap()
+        .env("stdout", "version: [")
+        .assert()
+        .stdout_yaml(serde_yaml::from_str("version: 1.2.3").unwrap());
+}